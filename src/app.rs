@@ -0,0 +1,1894 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use tui::{
+    backend::Backend,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans},
+    widgets::{Block, Borders, Clear, ListState, Tabs},
+    Frame,
+};
+
+use crate::browser::DirBrowser;
+use crate::discover::{self, DiscoveredList};
+use crate::config::{Config, Launcher};
+use crate::notify;
+use crate::state::{self, Session, UiState};
+use crate::tasks::TaskRunner;
+use crate::theme::Theme;
+use crate::picker::{self, Picker};
+use crate::tmux;
+use crate::*;
+
+/// An entry of the top menu.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Tab {
+    Home,
+    Projects,
+    Workspace(String),
+    Board,
+    Week,
+    Timeline,
+    Git,
+    Add,
+    Delete,
+    Quit,
+}
+
+/// What takes over the terminal from the UI.
+pub enum Foreground {
+    /// A command for the project with the name, like the editor.
+    Command(String, Command),
+    /// A shell in the directory of the project with the name. How it exits is up to the
+    /// last command run in it, so that isn't an error.
+    Shell(String, Command),
+    /// An external picker choosing the directory of a new project, starting at the path.
+    Picker(Box<dyn Picker>, PathBuf),
+}
+
+/// The state of the interactive UI between two events.
+pub struct App {
+    config: Config,
+    theme: Theme,
+    active_menu_item: MenuItem,
+    project_list_state: ListState,
+    view: ListView,
+    prompt: Option<Prompt>,
+    add_form: Option<AddForm>,
+    edit_form: Option<EditForm>,
+    rename: Option<InlineRename>,
+    dir_browser: Option<DirBrowser>,
+    /// Where the running discovery sends the directories it found.
+    discovery: Option<mpsc::Receiver<Vec<PathBuf>>>,
+    /// The directories found by the last discovery, to choose from.
+    discovered: Option<DiscoveredList>,
+    command_palette: Option<CommandPalette>,
+    /// The open sort menu, with the highlighted entry of `SortMode::ALL`.
+    sort_menu: Option<ListState>,
+    /// The open 'O' menu, with the highlighted launcher of the config, or the file
+    /// manager after them.
+    open_with_menu: Option<ListState>,
+    /// What was last saved to the state file.
+    saved_state: UiState,
+    /// Whether choices like the sort are saved for the next run, not when the picker was
+    /// given its own sort.
+    remember_state: bool,
+    focus: Focus,
+    /// The focused column of the board and the selected card in it.
+    board_column: usize,
+    board_state: ListState,
+    todo_list_state: ListState,
+    link_list_state: ListState,
+    /// Directory listing of the selected project, read again only when the selection moves
+    /// to another directory.
+    preview: Option<(String, io::Result<Vec<String>>)>,
+    /// The git remotes of the directory of the selected project.
+    remotes: Option<(String, Vec<git::Remote>)>,
+    remote_list_state: ListState,
+    git_status: git::StatusCache,
+    languages: languages::Cache,
+    usage: usage::UsageScanner,
+    show_readme: bool,
+    /// README of the selected project, by directory, read when the pane is shown.
+    readme: Option<(String, Option<String>)>,
+    show_help: bool,
+    display: DisplayOptions,
+    /// Notifications currently shown, oldest first.
+    toasts: Vec<Toast>,
+    tasks: TaskRunner,
+    /// The open task list, with the highlighted task.
+    task_list: Option<ListState>,
+    /// The workspaces shown as tabs, with their number of projects.
+    workspaces: Vec<(String, usize)>,
+    /// IDs of the projects marked for a bulk action.
+    marked: Vec<String>,
+    /// The projects removed by the last delete, until they're restored with 'u'.
+    deleted: Vec<DeletedProject>,
+    // Where the tabs and the project list were last drawn, for mapping mouse clicks.
+    tabs_area: Rect,
+    list_area: Rect,
+    detail_area: Rect,
+    list_offset: usize,
+    /// What each line of the drawn list shows.
+    list_lines: Vec<ListLine>,
+    /// Key of the section header selected instead of a project, see `GroupBy::key`.
+    selected_header: Option<String>,
+    /// Selects the line of the selected project in the drawn list, which differs from its
+    /// index when there are section headers.
+    list_display_state: ListState,
+    /// Started by `whisk pick`: Enter chooses the selected project and quits.
+    picker: bool,
+    /// The project chosen in the picker.
+    pub picked: Option<Project>,
+    /// What takes over the terminal next, which `run_ui` hands it to since it owns it.
+    pub foreground: Option<Foreground>,
+    pub quit: bool,
+}
+
+impl App {
+    pub fn new(config: Config, theme: Theme) -> App {
+        let mut project_list_state = ListState::default();
+        project_list_state.select(Some(0));
+        let saved_state = state::load();
+        let display = DisplayOptions {
+            nerd_font: config.nerd_font,
+            ..DisplayOptions::default()
+        };
+        App {
+            config,
+            theme,
+            active_menu_item: MenuItem::Home,
+            project_list_state,
+            view: ListView {
+                sort_mode: saved_state.sort_mode,
+                group_by: saved_state.group_by,
+                collapsed_groups: saved_state.collapsed_groups.clone(),
+                ..ListView::default()
+            },
+            prompt: None,
+            add_form: None,
+            edit_form: None,
+            rename: None,
+            dir_browser: None,
+            discovery: None,
+            discovered: None,
+            command_palette: None,
+            sort_menu: None,
+            open_with_menu: None,
+            saved_state,
+            remember_state: true,
+            focus: Focus::Projects,
+            board_column: 0,
+            board_state: ListState::default(),
+            todo_list_state: ListState::default(),
+            link_list_state: ListState::default(),
+            preview: None,
+            remotes: None,
+            remote_list_state: ListState::default(),
+            git_status: git::StatusCache::new(),
+            languages: languages::Cache::default(),
+            usage: usage::UsageScanner::new(),
+            show_readme: false,
+            readme: None,
+            show_help: false,
+            display,
+            toasts: Vec::new(),
+            tasks: TaskRunner::new(),
+            task_list: None,
+            workspaces: Vec::new(),
+            marked: Vec::new(),
+            deleted: Vec::new(),
+            tabs_area: Rect::default(),
+            list_area: Rect::default(),
+            detail_area: Rect::default(),
+            list_offset: 0,
+            list_lines: Vec::new(),
+            selected_header: None,
+            list_display_state: ListState::default(),
+            picker: false,
+            picked: None,
+            foreground: None,
+            quit: false,
+        }
+    }
+
+    fn success(&mut self, text: String) {
+        self.toasts.push(Toast::new(ToastKind::Success, text));
+    }
+
+    fn warning(&mut self, text: String) {
+        self.toasts.push(Toast::new(ToastKind::Warning, text));
+    }
+
+    fn error(&mut self, text: String) {
+        self.toasts.push(Toast::new(ToastKind::Error, text));
+    }
+
+    /// Reports finished background tasks and dismisses the notifications that have been
+    /// shown long enough.
+    pub fn tick(&mut self) {
+        for finished in self.tasks.finished() {
+            // The user may have left for another window while a long task ran.
+            let notify = self.config.notifications && !finished.cancelled && finished.elapsed >= notify::LONG_TASK;
+            match finished.outcome {
+                _ if finished.cancelled => self.warning(format!("Cancelled: {}", finished.label)),
+                Ok(message) => {
+                    if notify {
+                        notify::send(&finished.label, &message);
+                    }
+                    self.success(message)
+                }
+                Err(err) => {
+                    let message = format!("{} failed: {}", finished.label, err);
+                    if notify {
+                        notify::send(&finished.label, &message);
+                    }
+                    self.error(message)
+                }
+            }
+        }
+        if let Some(list_state) = &mut self.task_list {
+            let amount = self.tasks.running.len();
+            list_state.select(list_state.selected().map(|i| i.min(amount.saturating_sub(1))));
+        }
+        if let Some(receiver) = &self.discovery {
+            match receiver.try_recv() {
+                Ok(directories) => {
+                    self.discovery = None;
+                    if !directories.is_empty() {
+                        self.discovered = Some(DiscoveredList::new(directories));
+                    }
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.discovery = None,
+                Err(mpsc::TryRecvError::Empty) => {}
+            }
+        }
+        let measured = self.usage.finished();
+        if !measured.is_empty() {
+            if let Err(err) = store_disk_usage(&measured) {
+                self.error(format!("Saving disk usage failed: {}", err));
+            }
+        }
+        self.toasts.retain(|toast| !toast.is_expired());
+    }
+
+    pub fn draw<B: Backend>(&mut self, rect: &mut Frame<B>) {
+        let theme = &self.theme.clone();
+        let size = rect.size();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(2)
+            .constraints(
+                [
+                    Constraint::Length(3),
+                    Constraint::Min(2),
+                    Constraint::Length(3),
+                ]
+                .as_ref(),
+            )
+            .split(size);
+
+        self.workspaces = workspaces(&read_db().unwrap_or_default());
+        let tabs = self.tabs();
+        let menu = tabs
+            .iter()
+            .map(|(_, t)| {
+                let (first, rest) = t.split_at(1);
+                Spans::from(vec![
+                    Span::styled(
+                        first,
+                        Style::default()
+                            .fg(theme.accent)
+                            .add_modifier(Modifier::UNDERLINED),
+                    ),
+                    Span::styled(rest, theme.text()),
+                ])
+            })
+            .collect();
+
+        let current = self.current_tab();
+        let tabs = Tabs::new(menu)
+            .select(tabs.iter().position(|(tab, _)| *tab == current).unwrap_or(0))
+            .block(
+                Block::default()
+                    .title("Menu")
+                    .borders(Borders::ALL)
+                    .border_style(theme.border(false)),
+            )
+            .style(theme.text())
+            .highlight_style(Style::default().fg(theme.accent))
+            .divider(Span::raw("|"));
+
+        rect.render_widget(tabs, chunks[0]);
+        self.tabs_area = chunks[0];
+        self.list_area = Rect::default();
+        match self.active_menu_item {
+            MenuItem::Home => draw_home(rect, chunks[1], theme),
+            MenuItem::Week => rect.render_widget(render_week(theme), chunks[1]),
+            MenuItem::Timeline => rect.render_widget(
+                render_timeline(&read_db().unwrap_or_default(), chunks[1].width, theme),
+                chunks[1],
+            ),
+            MenuItem::Git => {
+                let repositories = read_db()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|project| Path::new(&project.directory).join(".git").exists())
+                    .map(|project| {
+                        let status = self.git_status.get(&project.directory).cloned();
+                        (project, status)
+                    })
+                    .collect();
+                rect.render_widget(render_repositories(repositories, theme), chunks[1]);
+            }
+            MenuItem::Projects => self.draw_projects(rect, chunks[1], theme),
+            MenuItem::Board => self.draw_board(rect, chunks[1], theme),
+        }
+        if self.show_help {
+            let area = centered_rect(90, chunks[1].height, chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_widget(render_help(theme), area);
+        }
+        if let Some(prompt) = &self.prompt {
+            rect.render_widget(render_prompt(prompt, theme), chunks[2]);
+        } else if !self.tasks.running.is_empty() {
+            rect.render_widget(render_task_status(&self.tasks, theme), chunks[2]);
+        } else {
+            rect.render_widget(render_key_hints(&self.key_hints(), theme), chunks[2]);
+        }
+        if let Some(list_state) = &mut self.task_list {
+            let area = centered_rect(60, self.tasks.running.len() as u16 + 2, chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(render_task_list(&self.tasks, theme), area, list_state);
+        }
+        if let (MenuItem::Projects, Some(form)) = (self.active_menu_item, &self.edit_form) {
+            rect.render_widget(Clear, self.detail_area);
+            rect.render_widget(render_edit_form(form, theme), self.detail_area);
+        }
+        if let Some(form) = &self.add_form {
+            let area = centered_rect(60, 9, chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_widget(render_add_form(form, theme), area);
+        }
+        if let Some(menu_state) = &mut self.sort_menu {
+            let area = centered_rect(30, SortMode::ALL.len() as u16 + 2, chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(render_sort_menu(self.view.sort_mode, theme), area, menu_state);
+        }
+        if let Some(menu_state) = &mut self.open_with_menu {
+            let area = centered_rect(30, self.config.launchers.len() as u16 + 3, chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(render_open_with_menu(&self.config.launchers, theme), area, menu_state);
+        }
+        if let Some(palette) = &mut self.command_palette {
+            let area = centered_rect(60, chunks[1].height.min(COMMANDS.len() as u16 + 2), chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(render_command_palette(palette, theme), area, &mut palette.state);
+        }
+        if let Some(browser) = &mut self.dir_browser {
+            let area = centered_rect(80, chunks[1].height, chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(browser.render(theme), area, &mut browser.state);
+        }
+        if let Some(list) = &mut self.discovered {
+            let area = centered_rect(80, chunks[1].height, chunks[1]);
+            rect.render_widget(Clear, area);
+            rect.render_stateful_widget(list.render(theme), area, &mut list.state);
+        }
+        // Newest first, as many as fit.
+        let mut top = 0;
+        for toast in self.toasts.iter().rev() {
+            let area = toast_area(&toast.text, top, chunks[1]);
+            if area.height < 3 {
+                break;
+            }
+            rect.render_widget(Clear, area);
+            rect.render_widget(render_toast(toast, theme), area);
+            top += area.height;
+        }
+    }
+
+    /// The keys that do something in the current view or mode, for the bottom bar.
+    fn key_hints(&self) -> Vec<(&'static str, &'static str)> {
+        if self.dir_browser.is_some() {
+            vec![("↑↓", "move"), ("Enter", "open"), ("Backspace", "up"), (".", "hidden files"), ("Space", "pick"), ("Esc", "close")]
+        } else if self.discovered.is_some() {
+            vec![("↑↓", "move"), ("Space", "choose"), ("a", "all"), ("Enter", "add chosen"), ("Esc", "close")]
+        } else if self.task_list.is_some() {
+            vec![("↑↓", "move"), ("x", "cancel task"), ("Esc", "close")]
+        } else if self.command_palette.is_some() {
+            vec![("type", "filter"), ("↑↓", "move"), ("Enter", "run"), ("Esc", "close")]
+        } else if self.sort_menu.is_some() {
+            vec![("↑↓", "move"), ("Enter", "sort"), ("Esc", "close")]
+        } else if self.open_with_menu.is_some() {
+            vec![("↑↓", "move"), ("Enter", "open"), ("Esc", "close")]
+        } else if self.add_form.is_some() {
+            vec![("Tab", "next field"), ("Ctrl+B", "browse"), ("Enter", "add"), ("Esc", "cancel")]
+        } else if self.edit_form.is_some() {
+            vec![("Tab", "next field"), ("←→", "status"), ("Enter", "save"), ("Esc", "cancel")]
+        } else if self.rename.is_some() {
+            vec![("←→", "move cursor"), ("Enter", "save"), ("Esc", "cancel")]
+        } else if self.show_help {
+            vec![("Esc", "close")]
+        } else {
+            let mut hints = match (self.active_menu_item, self.focus) {
+                (MenuItem::Home, _) => vec![("1-5", "open recent"), ("p", "projects"), ("a", "add")],
+                (MenuItem::Week | MenuItem::Timeline | MenuItem::Git, _) => vec![("p", "projects"), ("h", "home")],
+                (MenuItem::Board, _) => vec![("Tab", "column"), ("←→", "move card"), ("p", "projects")],
+                (MenuItem::Projects, Focus::Todos) => {
+                    vec![("Space", "toggle"), ("d", "remove"), ("t", "add"), ("Esc", "back")]
+                }
+                (MenuItem::Projects, Focus::Links) => vec![("Enter", "go to"), ("Tab", "remotes"), ("Esc", "back")],
+                (MenuItem::Projects, Focus::Remotes) => vec![("Enter", "open"), ("y", "copy URL"), ("Esc", "back")],
+                (MenuItem::Projects, Focus::Projects) if self.picker => {
+                    vec![("Enter", "go to project"), ("/", "search"), ("s", "sort"), ("f", "filter")]
+                }
+                (MenuItem::Projects, Focus::Projects) => vec![
+                    ("Enter", "open"),
+                    ("a", "add"),
+                    ("e", "edit"),
+                    ("r", "rename"),
+                    ("d", "delete"),
+                    ("Space", "mark"),
+                    ("/", "search"),
+                    ("s", "sort"),
+                    ("f", "filter"),
+                ],
+            };
+            if !self.marked.is_empty() && self.active_menu_item == MenuItem::Projects {
+                hints.push(("Esc", "unmark"));
+            }
+            hints.extend([("Ctrl+P", "commands"), ("?", "help")]);
+            if self.active_menu_item != MenuItem::Projects || self.focus == Focus::Projects {
+                hints.push(("q", "quit"));
+            }
+            hints
+        }
+    }
+
+    /// The tabs of the top menu with their titles, one per workspace after "Projects".
+    fn tabs(&self) -> Vec<(Tab, String)> {
+        let mut tabs = vec![(Tab::Home, "Home".to_string()), (Tab::Projects, "Projects".to_string())];
+        for (i, (name, count)) in self.workspaces.iter().enumerate() {
+            tabs.push((Tab::Workspace(name.clone()), format!("{} {} ({})", i + 1, name, count)));
+        }
+        tabs.extend([
+            (Tab::Board, "Board".to_string()),
+            (Tab::Week, "Week".to_string()),
+            (Tab::Timeline, "Timeline".to_string()),
+            (Tab::Git, "Git".to_string()),
+        ]);
+        if !is_read_only() {
+            tabs.extend([(Tab::Add, "Add".to_string()), (Tab::Delete, "Delete".to_string())]);
+        }
+        tabs.push((Tab::Quit, "Quit".to_string()));
+        tabs
+    }
+
+    fn current_tab(&self) -> Tab {
+        match (self.active_menu_item, &self.view.workspace) {
+            (MenuItem::Home, _) => Tab::Home,
+            (MenuItem::Week, _) => Tab::Week,
+            (MenuItem::Timeline, _) => Tab::Timeline,
+            (MenuItem::Git, _) => Tab::Git,
+            (MenuItem::Board, _) => Tab::Board,
+            (MenuItem::Projects, None) => Tab::Projects,
+            (MenuItem::Projects, Some(workspace)) => Tab::Workspace(workspace.clone()),
+        }
+    }
+
+    /// Shows the projects of a workspace, or all of them for `None`.
+    fn show_workspace(&mut self, workspace: Option<String>) {
+        self.active_menu_item = MenuItem::Projects;
+        if self.view.workspace != workspace {
+            self.view.workspace = workspace;
+            self.project_list_state.select(Some(0));
+        }
+    }
+
+    /// The line of the drawn list with the selected project or section header.
+    fn selected_line(&self) -> Option<usize> {
+        let selected = match (self.project_list_state.selected(), &self.selected_header) {
+            (Some(index), _) => ListLine::Row(index),
+            (None, Some(header)) => {
+                return self
+                    .list_lines
+                    .iter()
+                    .position(|line| matches!(line, ListLine::Header { key, .. } if key == header))
+            }
+            (None, None) => return None,
+        };
+        self.list_lines.iter().position(|line| *line == selected)
+    }
+
+    fn select_line(&mut self, line: &ListLine) {
+        match line {
+            ListLine::Row(index) => {
+                self.project_list_state.select(Some(*index));
+                self.selected_header = None;
+            }
+            ListLine::Header { key, .. } => {
+                self.project_list_state.select(None);
+                self.selected_header = Some(key.clone());
+            }
+        }
+    }
+
+    /// Moves the selection by one line, section headers included, wrapping around at the ends.
+    fn move_line(&mut self, forward: bool) {
+        let amount = self.list_lines.len();
+        if amount == 0 {
+            return;
+        }
+        let line = match self.selected_line() {
+            Some(line) if forward => (line + 1) % amount,
+            Some(line) => (line + amount - 1) % amount,
+            None => 0,
+        };
+        self.select_line(&self.list_lines[line].clone());
+    }
+
+    /// Collapses or expands the section with `key`.
+    fn toggle_group(&mut self, key: String) {
+        match self.view.collapsed_groups.iter().position(|k| *k == key) {
+            Some(index) => {
+                self.view.collapsed_groups.remove(index);
+            }
+            None => self.view.collapsed_groups.push(key),
+        }
+    }
+
+    /// Draws a scrollbar on the right border of the list and the position of the selected
+    /// project, like "12/87", on the bottom border.
+    fn draw_list_position<B: Backend>(&self, rect: &mut Frame<B>, theme: &Theme) {
+        let area = self.list_area;
+        let total = self.list_lines.iter().filter(|line| matches!(line, ListLine::Row(_))).count();
+        if total == 0 || area.height < 3 || area.width < 3 {
+            return;
+        }
+
+        let height = (area.height - 2) as usize;
+        let lines = self.list_lines.len();
+        if lines > height {
+            let thumb = (height * height / lines).max(1);
+            let start = self.list_offset * (height - thumb) / (lines - height);
+            let lines: Vec<Spans> = (0..height)
+                .map(|i| {
+                    if (start..start + thumb).contains(&i) {
+                        Spans::from(Span::styled("█", Style::default().fg(theme.accent)))
+                    } else {
+                        Spans::from(Span::styled("│", theme.border(false)))
+                    }
+                })
+                .collect();
+            let track = Rect::new(area.x + area.width - 1, area.y + 1, 1, height as u16);
+            rect.render_widget(Paragraph::new(lines), track);
+        }
+
+        let selected = self.project_list_state.selected().unwrap_or(0).min(total - 1);
+        let position = format!(" {}/{} ", selected + 1, total);
+        let width = (position.chars().count() as u16).min(area.width - 2);
+        let label = Rect::new(area.x + area.width - 1 - width, area.y + area.height - 1, width, 1);
+        rect.render_widget(Paragraph::new(Span::styled(position, theme.muted())), label);
+    }
+
+    /// Draws the name being edited over the selected line of the list, with the cursor in it.
+    fn draw_rename<B: Backend>(&self, rect: &mut Frame<B>, rename: &InlineRename, line: usize, theme: &Theme) {
+        let row = line.saturating_sub(self.list_offset) as u16;
+        if self.list_area.height < 3 || row >= self.list_area.height - 2 {
+            return;
+        }
+        let area = Rect::new(self.list_area.x + 1, self.list_area.y + 1 + row, self.list_area.width.saturating_sub(2), 1);
+        let prefix = "✎ ";
+        let room = (area.width as usize).saturating_sub(prefix.chars().count() + 1);
+        // Scroll long names so the cursor stays in view.
+        let skip = rename.cursor.saturating_sub(room);
+        let visible: String = rename.input.chars().skip(skip).take(room + 1).collect();
+        let line = Paragraph::new(Spans::from(vec![
+            Span::styled(prefix, Style::default().fg(theme.accent)),
+            Span::raw(visible),
+        ]))
+        .style(theme.text().add_modifier(Modifier::UNDERLINED));
+        rect.render_widget(Clear, area);
+        rect.render_widget(line, area);
+        rect.set_cursor(area.x + (prefix.chars().count() + rename.cursor - skip) as u16, area.y);
+    }
+
+    fn draw_board<B: Backend>(&mut self, rect: &mut Frame<B>, area: Rect, theme: &Theme) {
+        let columns = board_columns(&self.view).unwrap_or_default();
+        let areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Ratio(1, BOARD_COLUMNS.len() as u32); BOARD_COLUMNS.len()])
+            .split(area);
+        for (i, (status, projects)) in BOARD_COLUMNS.iter().zip(columns.iter()).enumerate() {
+            let focused = i == self.board_column;
+            let column = render_board_column(*status, projects, focused, theme);
+            if focused {
+                rect.render_stateful_widget(column, areas[i], &mut self.board_state);
+            } else {
+                rect.render_widget(column, areas[i]);
+            }
+        }
+    }
+
+    fn draw_projects<B: Backend>(&mut self, rect: &mut Frame<B>, area: Rect, theme: &Theme) {
+        // Narrow terminals get the list on top of the detail instead of beside it.
+        let projects_chunks = if area.width < NARROW_WIDTH {
+            Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)].as_ref())
+                .split(area)
+        } else {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(
+                    [Constraint::Percentage(20), Constraint::Percentage(80)].as_ref(),
+                )
+                .split(area)
+        };
+        let project = selected_project(&self.project_list_state, &self.view).unwrap_or_default();
+        let notes = project.as_ref().map(|p| p.notes.clone()).unwrap_or_default();
+        let notes_height = if notes.is_empty() { 0 } else { 3 };
+        let directory_lines = match &project {
+            Some(project) if self.display.full_paths => {
+                wrap_path(&project.directory, directory_width(projects_chunks[1].width, self.display)).len() as u16
+            }
+            _ => 1,
+        };
+        let detail_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(detail_height(self.display, directory_lines)), Constraint::Length(notes_height), Constraint::Min(3)].as_ref())
+            .split(projects_chunks[1]);
+        self.detail_area = projects_chunks[1];
+        if let Some(project) = &project {
+            if project.disk_usage.is_none_or(|usage| usage.is_stale()) {
+                self.usage.request(&project.directory);
+            }
+        }
+        let git_status = project.as_ref().and_then(|p| self.git_status.get(&p.directory));
+        let (left, right) =
+            render_projects(
+                &self.project_list_state,
+                &self.view,
+                &self.marked,
+                git_status,
+                &mut self.languages,
+                self.display,
+                projects_chunks[1].width,
+                theme,
+            );
+        let last_commit = render_last_commit(git_status, theme);
+        self.list_area = projects_chunks[0];
+        self.list_lines = grouped_tree(&self.view).map(|(_, lines)| lines).unwrap_or_default();
+        let selected_line = self.selected_line();
+        self.list_display_state.select(selected_line);
+        // Mirror the List widget's scrolling to know which line is at the top.
+        let list_height = self.list_area.height.saturating_sub(2) as usize;
+        if let Some(selected) = selected_line {
+            if selected >= self.list_offset + list_height {
+                self.list_offset = selected + 1 - list_height;
+            } else if selected < self.list_offset {
+                self.list_offset = selected;
+            }
+        }
+        rect.render_stateful_widget(left, projects_chunks[0], &mut self.list_display_state);
+        self.draw_list_position(rect, theme);
+        if let (Some(rename), Some(selected)) = (&self.rename, selected_line) {
+            self.draw_rename(rect, rename, selected, theme);
+        }
+        if let Some(right) = right {
+            rect.render_widget(right, detail_chunks[0]);
+            let detail = detail_chunks[0];
+            if project.is_some() && detail.height > 2 {
+                let line = Rect::new(detail.x + 1, detail.bottom() - 2, detail.width.saturating_sub(2), 1);
+                rect.render_widget(last_commit, line);
+            }
+        }
+        if !notes.is_empty() {
+            rect.render_widget(render_notes(&notes, theme), detail_chunks[1]);
+        }
+        if self.show_readme {
+            if let Some(project) = &project {
+                if self.readme.as_ref().is_none_or(|(dir, _)| *dir != project.directory) {
+                    let content = markdown::find_readme(Path::new(&project.directory))
+                        .and_then(|path| fs::read_to_string(path).ok());
+                    self.readme = Some((project.directory.clone(), content));
+                }
+                let content = self.readme.as_ref().and_then(|(_, content)| content.as_deref());
+                rect.render_widget(render_readme(content, theme), detail_chunks[2]);
+            }
+            return;
+        }
+        let extra_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(
+                [
+                    Constraint::Percentage(40),
+                    Constraint::Percentage(25),
+                    Constraint::Percentage(35),
+                ]
+                .as_ref(),
+            )
+            .split(detail_chunks[2]);
+        rect.render_stateful_widget(
+            render_todos(project.as_ref(), self.focus == Focus::Todos, theme),
+            extra_chunks[0],
+            &mut self.todo_list_state,
+        );
+        rect.render_stateful_widget(
+            render_links(project.as_ref(), self.focus == Focus::Links, theme),
+            extra_chunks[1],
+            &mut self.link_list_state,
+        );
+        if let Some(project) = &project {
+            let stale = self.preview.as_ref().is_none_or(|(dir, _)| *dir != project.directory);
+            if stale {
+                let entries = browser::preview(Path::new(&project.directory));
+                self.preview = Some((project.directory.clone(), entries));
+            }
+        }
+        let remotes = self.selected_remotes();
+        let remotes_height = if remotes.is_empty() { 0 } else { remotes.len().min(4) as u16 + 2 };
+        let side_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(remotes_height), Constraint::Min(0)].as_ref())
+            .split(extra_chunks[2]);
+        if !remotes.is_empty() {
+            rect.render_stateful_widget(
+                render_remotes(&remotes, self.focus == Focus::Remotes, theme),
+                side_chunks[0],
+                &mut self.remote_list_state,
+            );
+        }
+        if let (Some(_), Some((_, entries))) = (&project, &self.preview) {
+            rect.render_widget(render_preview(entries, theme), side_chunks[1]);
+        }
+    }
+
+    /// Rows of the project list that fit on screen, at least one.
+    fn page_size(&self) -> isize {
+        self.list_area.height.saturating_sub(2).max(1) as isize
+    }
+
+    /// Turns the UI into a picker that starts searching the project list and quits once
+    /// a project is chosen with Enter. A sort given here is only for this run.
+    pub fn start_picker(&mut self, sort: Option<SortMode>) {
+        self.picker = true;
+        if let Some(sort) = sort {
+            self.view.sort_mode = sort;
+            self.remember_state = false;
+        }
+        self.show_workspace(None);
+        self.prompt = Some(Prompt::new(PromptKind::Search, String::new()));
+    }
+
+    /// Goes back to the tab, filters and selected project of the last run. A workspace
+    /// that no project is in any more has no tab to go back to.
+    pub fn restore_session(&mut self) -> Result<(), Error> {
+        let session = self.saved_state.session.clone();
+        let projects = read_db()?;
+        self.active_menu_item = session.tab;
+        self.view.workspace = session
+            .workspace
+            .filter(|workspace| projects.iter().any(|p| p.workspace.as_ref() == Some(workspace)));
+        self.view.status_filter = session.status_filter;
+        self.view.search = session.search;
+        if let Some(id) = session.selected {
+            if let Some(index) = visible_projects(&self.view)?.iter().position(|p| p.id == id) {
+                self.project_list_state.select(Some(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Switches to the project's tmux session inside tmux, or attaches to it in place of
+    /// the UI until it's detached.
+    fn open_tmux_session(&mut self, project: Project) -> Result<(), Error> {
+        let name = tmux::session_name(&project.name);
+        if tmux::inside() {
+            match tmux::switch(&name, &project.directory) {
+                Ok(()) => self.success(format!("Switched to the tmux session '{}'", name)),
+                Err(err) => {
+                    self.error(err);
+                    return Ok(());
+                }
+            }
+        } else {
+            let command = tmux::attach_command(&name, &project.directory);
+            self.foreground = Some(Foreground::Command(project.name.clone(), command));
+        }
+        mark_opened(&project.id)
+    }
+
+    /// Chooses the directory of a new project with the picker of the config, starting at
+    /// `start`, or with the built-in browser if it's that or the tool isn't installed.
+    fn pick_directory(&mut self, start: PathBuf) {
+        match picker::find(&self.config.picker) {
+            Ok(Some(picker)) => self.pick_directory_with(picker, start),
+            Ok(None) => self.dir_browser = Some(DirBrowser::new(&start)),
+            Err(err) => {
+                self.warning(format!("{}, using the built-in browser", err));
+                self.dir_browser = Some(DirBrowser::new(&start));
+            }
+        }
+    }
+
+    fn pick_directory_with(&mut self, picker: Box<dyn Picker>, start: PathBuf) {
+        if picker.is_available() {
+            self.foreground = Some(Foreground::Picker(picker, start));
+        } else {
+            self.warning(format!("{} isn't available, using the built-in browser", picker.name()));
+            self.dir_browser = Some(DirBrowser::new(&start));
+        }
+    }
+
+    /// Puts the directory chosen with a picker into the add form, once the UI is back.
+    pub fn directory_picked(&mut self, picker: &str, result: Result<Option<PathBuf>, String>) {
+        match result {
+            Ok(Some(directory)) => self.fill_add_form(&directory),
+            Ok(None) => {}
+            Err(err) => self.error(format!("{} failed: {}", picker, err)),
+        }
+    }
+
+    /// Opens the add form, if it isn't open, with `directory` and the name field active.
+    fn fill_add_form(&mut self, directory: &Path) {
+        let form = self.add_form.get_or_insert_with(AddForm::default);
+        form.directory = directory.to_string_lossy().to_string();
+        form.field = 1;
+        form.error = None;
+    }
+
+    /// Reports how the command in `foreground` went, once the UI is back.
+    pub fn foreground_finished(&mut self, project: &str, result: Result<(), String>) {
+        // Whatever ran may have added or removed the files languages are told by.
+        self.languages.clear();
+        if let Err(err) = result {
+            self.error(format!("Can't open '{}': {}", project, err));
+        }
+    }
+
+    /// Chooses the selected project in the picker and quits.
+    fn pick(&mut self) -> Result<(), Error> {
+        if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+            mark_opened(&project.id)?;
+            self.picked = Some(project);
+            self.quit = true;
+        }
+        Ok(())
+    }
+
+    /// Handles a key press, reporting failures in the status bar.
+    pub fn handle_key(&mut self, event: KeyEvent) {
+        if let Err(err) = self.on_key(event).and_then(|_| self.save_state()) {
+            self.error(err.to_string());
+        }
+    }
+
+    /// Remembers the UI choices that changed for the next run.
+    fn save_state(&mut self) -> Result<(), Error> {
+        let mut current = UiState {
+            sort_mode: self.view.sort_mode,
+            group_by: self.view.group_by,
+            collapsed_groups: self.view.collapsed_groups.clone(),
+            ..self.saved_state.clone()
+        };
+        // The picker is a quick detour, not where the UI was left off.
+        if !self.picker {
+            current.session = Session {
+                tab: self.active_menu_item,
+                workspace: self.view.workspace.clone(),
+                status_filter: self.view.status_filter,
+                search: self.view.search.clone(),
+                selected: selected_project(&self.project_list_state, &self.view)?.map(|p| p.id),
+            };
+        }
+        if self.remember_state && current != self.saved_state {
+            state::save(&current)?;
+            self.saved_state = current;
+        }
+        Ok(())
+    }
+
+    /// Handles a mouse event, reporting failures in the status bar.
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) {
+        if let Err(err) = self.on_mouse(mouse).and_then(|_| self.save_state()) {
+            self.error(err.to_string());
+        }
+    }
+
+    fn on_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        if self.dir_browser.is_some() {
+            self.on_browser_key(event);
+            return Ok(());
+        }
+        if self.discovered.is_some() {
+            return self.on_discovered_key(event);
+        }
+        if self.task_list.is_some() {
+            self.on_task_list_key(event);
+            return Ok(());
+        }
+        if self.command_palette.is_some() {
+            return self.on_command_palette_key(event);
+        }
+        if self.sort_menu.is_some() {
+            return self.on_sort_menu_key(event);
+        }
+        if self.open_with_menu.is_some() {
+            return self.on_open_with_menu_key(event);
+        }
+        if self.add_form.is_some() {
+            return self.on_add_form_key(event);
+        }
+        if self.edit_form.is_some() {
+            return self.on_edit_form_key(event);
+        }
+        if self.prompt.is_some() {
+            return self.on_prompt_key(event);
+        }
+        if self.rename.is_some() {
+            return self.on_rename_key(event);
+        }
+        if self.show_help {
+            if matches!(event.code, KeyCode::Esc | KeyCode::Char('?')) {
+                self.show_help = false;
+            }
+            return Ok(());
+        }
+        if event.code == KeyCode::Char('?') {
+            self.show_help = true;
+            return Ok(());
+        }
+        if event.code == KeyCode::Char('p') && event.modifiers.contains(KeyModifiers::CONTROL) {
+            let mut palette = CommandPalette::default();
+            palette.state.select(Some(0));
+            self.command_palette = Some(palette);
+            return Ok(());
+        }
+        if event.code == KeyCode::Char('t') && event.modifiers.contains(KeyModifiers::CONTROL) {
+            let mut list_state = ListState::default();
+            list_state.select(if self.tasks.running.is_empty() { None } else { Some(0) });
+            self.task_list = Some(list_state);
+            return Ok(());
+        }
+        if is_read_only() && self.changes_projects(&event) {
+            self.warning("Read-only mode, projects can't be changed".to_string());
+            return Ok(());
+        }
+        if self.active_menu_item == MenuItem::Board {
+            return self.on_board_key(event);
+        }
+        match self.focus {
+            Focus::Todos => self.on_todos_key(event),
+            Focus::Links => self.on_links_key(event),
+            Focus::Remotes => self.on_remotes_key(event),
+            Focus::Projects => self.on_projects_key(event),
+        }
+    }
+
+    /// Whether `event` would add, edit or remove projects, which read-only mode refuses
+    /// before a form or prompt for it opens.
+    fn changes_projects(&self, event: &KeyEvent) -> bool {
+        if event.modifiers.intersects(KeyModifiers::ALT | KeyModifiers::CONTROL) {
+            return false;
+        }
+        if self.active_menu_item == MenuItem::Board {
+            return matches!(event.code, KeyCode::Left | KeyCode::Right);
+        }
+        match self.focus {
+            Focus::Todos => matches!(event.code, KeyCode::Char(' ' | 'd' | 't') | KeyCode::Enter),
+            Focus::Links | Focus::Remotes => false,
+            Focus::Projects => match event.code {
+                KeyCode::Up | KeyCode::Down => event.modifiers.contains(KeyModifiers::SHIFT),
+                KeyCode::Left | KeyCode::Right | KeyCode::F(2) => true,
+                KeyCode::Char(c) => "aBXduzreN#Wc+-APTDtlLJK".contains(c),
+                _ => false,
+            },
+        }
+    }
+
+    fn on_browser_key(&mut self, event: KeyEvent) {
+        let browser = self.dir_browser.as_mut().expect("there is an open browser");
+        match event.code {
+            KeyCode::Esc => self.dir_browser = None,
+            KeyCode::Down => browser.next(),
+            KeyCode::Up => browser.previous(),
+            KeyCode::Enter | KeyCode::Right => browser.descend(),
+            KeyCode::Backspace | KeyCode::Left => browser.ascend(),
+            KeyCode::Char('.') => browser.toggle_hidden(),
+            KeyCode::Char(' ') => {
+                let path = browser.highlighted().unwrap_or(&browser.current).to_path_buf();
+                self.dir_browser = None;
+                self.fill_add_form(&path);
+            }
+            _ => {}
+        }
+    }
+
+    fn on_discovered_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let list = self.discovered.as_mut().expect("there is an open discovered list");
+        match event.code {
+            KeyCode::Esc => self.discovered = None,
+            KeyCode::Down => list.next(),
+            KeyCode::Up => list.previous(),
+            KeyCode::Char(' ') => list.toggle(),
+            KeyCode::Char('a') => list.toggle_all(),
+            KeyCode::Enter => {
+                let added = add_directories(list.chosen())?;
+                self.discovered = None;
+                self.success(format!("Added {}", count_projects(added)));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Searches the project roots of the config for projects that aren't added yet in
+    /// the background, and lists them to choose from once done.
+    fn start_discovery(&mut self) {
+        let roots: Vec<PathBuf> = self
+            .config
+            .project_roots
+            .iter()
+            .filter_map(|root| expand_tilde(root).canonicalize().ok())
+            .collect();
+        if roots.is_empty() {
+            self.warning("Add project_roots to the config to discover projects".to_string());
+            return;
+        }
+        let (found, receiver) = mpsc::channel();
+        self.discovery = Some(receiver);
+        self.tasks.spawn("Discovering projects", move |cancel| {
+            let known: Vec<String> = read_db().map_err(|err| err.to_string())?.into_iter().map(|p| p.directory).collect();
+            let directories: Vec<PathBuf> = discover::discover(&roots, discover::DEFAULT_DEPTH, || cancel.is_cancelled())
+                .into_iter()
+                .filter(|directory| !known.contains(&directory.to_string_lossy().to_string()))
+                .collect();
+            if cancel.is_cancelled() {
+                return Err("cancelled".to_string());
+            }
+            let message = match directories.len() {
+                0 => "No new projects found".to_string(),
+                n => format!("Found {}", count_projects(n)),
+            };
+            let _ = found.send(directories);
+            Ok(message)
+        });
+    }
+
+    fn on_task_list_key(&mut self, event: KeyEvent) {
+        let list_state = self.task_list.as_mut().expect("there is an open task list");
+        let amount = self.tasks.running.len();
+        let selected = list_state.selected();
+        match event.code {
+            KeyCode::Esc => self.task_list = None,
+            KeyCode::Char('t') if event.modifiers.contains(KeyModifiers::CONTROL) => self.task_list = None,
+            KeyCode::Down if amount > 0 => list_state.select(Some(selected.map_or(0, |i| (i + 1) % amount))),
+            KeyCode::Up if amount > 0 => {
+                list_state.select(Some(selected.map_or(0, |i| (i + amount - 1) % amount)));
+            }
+            KeyCode::Char('x') | KeyCode::Delete => {
+                if let Some(i) = selected {
+                    self.tasks.cancel(i);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn on_command_palette_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let palette = self.command_palette.as_mut().expect("there is an open command palette");
+        let matches = palette.matches();
+        let selected = palette.state.selected().unwrap_or(0);
+        match event.code {
+            KeyCode::Esc => self.command_palette = None,
+            KeyCode::Down if !matches.is_empty() => palette.state.select(Some((selected + 1) % matches.len())),
+            KeyCode::Up if !matches.is_empty() => {
+                palette.state.select(Some((selected + matches.len() - 1) % matches.len()))
+            }
+            KeyCode::Enter => {
+                self.command_palette = None;
+                if let Some((_, code, modifiers)) = matches.get(selected) {
+                    return self.on_key(KeyEvent::new(*code, *modifiers));
+                }
+            }
+            KeyCode::Backspace => {
+                palette.query.pop();
+                palette.state.select(Some(0));
+            }
+            KeyCode::Char(c) => {
+                palette.query.push(c);
+                palette.state.select(Some(0));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_rename_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let rename = self.rename.as_mut().expect("there is a rename in progress");
+        match event.code {
+            KeyCode::Esc => self.rename = None,
+            KeyCode::Enter => match rename.submit() {
+                Ok(name) => {
+                    self.rename = None;
+                    self.success(format!("Renamed to '{}'", name));
+                }
+                Err(err) => self.error(err.to_string()),
+            },
+            KeyCode::Left => rename.move_cursor(-1),
+            KeyCode::Right => rename.move_cursor(1),
+            KeyCode::Home => rename.cursor = 0,
+            KeyCode::End => rename.move_cursor(isize::MAX),
+            KeyCode::Backspace => rename.backspace(),
+            KeyCode::Delete => rename.delete(),
+            KeyCode::Char(c) => rename.insert(c),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_sort_menu_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let menu_state = self.sort_menu.as_mut().expect("there is an open sort menu");
+        let selected = menu_state.selected().unwrap_or(0);
+        let amount = SortMode::ALL.len();
+        match event.code {
+            KeyCode::Esc | KeyCode::Char('s') => self.sort_menu = None,
+            KeyCode::Down => menu_state.select(Some((selected + 1) % amount)),
+            KeyCode::Up => menu_state.select(Some((selected + amount - 1) % amount)),
+            KeyCode::Enter => {
+                self.view.sort_mode = SortMode::ALL[selected];
+                self.sort_menu = None;
+                self.project_list_state.select(Some(0));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_open_with_menu_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let menu_state = self.open_with_menu.as_mut().expect("there is an open 'Open with' menu");
+        let selected = menu_state.selected().unwrap_or(0);
+        let amount = self.config.launchers.len() + 1;
+        match event.code {
+            KeyCode::Esc | KeyCode::Char('O') => self.open_with_menu = None,
+            KeyCode::Down => menu_state.select(Some((selected + 1) % amount)),
+            KeyCode::Up => menu_state.select(Some((selected + amount - 1) % amount)),
+            KeyCode::Enter => {
+                self.open_with_menu = None;
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    match self.config.launchers.get(selected).cloned() {
+                        Some(launcher) => self.open_with(project, &launcher)?,
+                        None => {
+                            mark_opened(&project.id)?;
+                            open_externally(&project.directory)?;
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Opens the project's directory with the app, handing it the terminal if it runs there.
+    fn open_with(&mut self, project: Project, launcher: &Launcher) -> Result<(), Error> {
+        let Some(mut command) = command_for_directory(&launcher.command, &project.directory) else {
+            self.error(format!("The command of '{}' is empty", launcher.name));
+            return Ok(());
+        };
+        command.current_dir(&project.directory);
+        if launcher.terminal {
+            self.foreground = Some(Foreground::Command(project.name.clone(), command));
+        } else {
+            let started = command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null()).spawn();
+            if let Err(err) = started {
+                self.error(format!("Can't start {}: {}", launcher.name, err));
+                return Ok(());
+            }
+        }
+        mark_opened(&project.id)
+    }
+
+    fn on_add_form_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let form = self.add_form.as_mut().expect("there is an open form");
+        match event.code {
+            KeyCode::Esc => self.add_form = None,
+            KeyCode::Char('b') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                let start = expand_tilde(form.directory.trim());
+                let start = if start.is_dir() { start } else { std::env::current_dir().unwrap_or_default() };
+                self.pick_directory(start);
+            }
+            KeyCode::Tab | KeyCode::BackTab | KeyCode::Up | KeyCode::Down => form.field = 1 - form.field,
+            KeyCode::Backspace => {
+                form.input().pop();
+            }
+            KeyCode::Char(c) => form.input().push(c),
+            KeyCode::Enter => match form.submit() {
+                Ok(name) => {
+                    self.add_form = None;
+                    self.success(format!("Added '{}'", name));
+                }
+                Err(err) => form.error = Some(err.to_string()),
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_edit_form_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let form = self.edit_form.as_mut().expect("there is an open form");
+        match event.code {
+            KeyCode::Esc => self.edit_form = None,
+            KeyCode::Tab | KeyCode::Down => form.next_field(),
+            KeyCode::BackTab | KeyCode::Up => form.previous_field(),
+            KeyCode::Left if form.input().is_none() => form.status = form.status.previous(),
+            KeyCode::Right | KeyCode::Char(' ') if form.input().is_none() => form.status = form.status.next(),
+            KeyCode::Backspace => {
+                if let Some(input) = form.input() {
+                    input.pop();
+                }
+            }
+            KeyCode::Char(c) => {
+                if let Some(input) = form.input() {
+                    input.push(c);
+                }
+            }
+            KeyCode::Enter => match form.submit() {
+                Ok(()) => {
+                    let name = form.name.trim().to_string();
+                    self.edit_form = None;
+                    self.success(format!("Saved '{}'", name));
+                }
+                Err(err) => form.error = Some(err.to_string()),
+            },
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_prompt_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let current = self.prompt.as_mut().expect("there is an open prompt");
+        match event.code {
+            KeyCode::Esc => {
+                if current.kind == PromptKind::Search {
+                    self.view.search.clear();
+                }
+                self.prompt = None;
+            }
+            KeyCode::Backspace => {
+                current.input.pop();
+            }
+            KeyCode::Char(c) => current.input.push(c),
+            // The picker keeps searching while the selection moves and picks right away.
+            KeyCode::Down | KeyCode::Up if self.picker && current.kind == PromptKind::Search => {
+                let offset = if event.code == KeyCode::Down { 1 } else { -1 };
+                select_project_by_offset(&mut self.project_list_state, &self.view, offset)?;
+            }
+            KeyCode::Enter if self.picker && current.kind == PromptKind::Search => {
+                self.prompt = None;
+                self.pick()?;
+            }
+            KeyCode::Enter if current.kind == PromptKind::Search => self.prompt = None,
+            KeyCode::Enter => {
+                let bulk = matches!(current.kind, PromptKind::Tags | PromptKind::Workspace);
+                let targets = if bulk {
+                    bulk_targets(&self.marked, &self.project_list_state, &self.view)?
+                } else {
+                    Vec::new()
+                };
+                let state = &mut self.project_list_state;
+                let view = &self.view;
+                let result = match current.kind {
+                    PromptKind::Tags => edit_tags(&targets, &current.input),
+                    PromptKind::Workspace => {
+                        let workspace = Some(current.input.trim().to_string()).filter(|w| !w.is_empty());
+                        update_projects(&targets, |p| p.workspace = workspace.clone())
+                    }
+                    PromptKind::Search => Ok(()),
+                    PromptKind::Alias => set_alias_at_index(state, view, &current.input),
+                    PromptKind::Todo => add_todo_at_index(state, view, &current.input),
+                    PromptKind::Link(kind) => toggle_link_at_index(state, view, kind, &current.input),
+                    PromptKind::DueDate => set_due_date_at_index(state, view, &current.input),
+                    PromptKind::Parent => set_parent_at_index(state, view, &current.input),
+                    PromptKind::Jump => jump_to_project(state, view, &current.input)
+                        .map(|_| self.active_menu_item = MenuItem::Projects),
+                };
+                match result {
+                    Ok(()) => {
+                        self.prompt = None;
+                        if bulk {
+                            self.marked.clear();
+                        }
+                    }
+                    Err(err) => current.error = Some(err.to_string()),
+                }
+            }
+            _ => {}
+        }
+        // The search narrows the list as you type.
+        if let Some(Prompt { kind: PromptKind::Search, input, .. }) = &self.prompt {
+            if *input != self.view.search {
+                self.view.search = input.clone();
+                self.project_list_state.select(Some(0));
+            }
+        }
+        Ok(())
+    }
+
+    fn on_board_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let columns = board_columns(&self.view)?;
+        let cards = columns[self.board_column].len();
+        let selected = self.board_state.selected().filter(|&i| i < cards);
+        match event.code {
+            KeyCode::Tab | KeyCode::BackTab => {
+                let amount = BOARD_COLUMNS.len();
+                self.board_column = if event.code == KeyCode::Tab {
+                    (self.board_column + 1) % amount
+                } else {
+                    (self.board_column + amount - 1) % amount
+                };
+                let cards = columns[self.board_column].len();
+                self.board_state.select(if cards > 0 { Some(0) } else { None });
+            }
+            KeyCode::Down if cards > 0 => self.board_state.select(Some(selected.map_or(0, |i| (i + 1) % cards))),
+            KeyCode::Up if cards > 0 => {
+                self.board_state.select(Some(selected.map_or(0, |i| (i + cards - 1) % cards)));
+            }
+            KeyCode::Left | KeyCode::Right => {
+                let Some(project) = selected.map(|i| &columns[self.board_column][i]) else {
+                    return Ok(());
+                };
+                let target = match event.code {
+                    KeyCode::Left => self.board_column.checked_sub(1),
+                    _ => Some(self.board_column + 1).filter(|&c| c < BOARD_COLUMNS.len()),
+                };
+                if let Some(target) = target {
+                    let status = BOARD_COLUMNS[target];
+                    update_projects(std::slice::from_ref(&project.id), |p| p.status = status)?;
+                    let id = project.id.clone();
+                    self.board_column = target;
+                    let position = board_columns(&self.view)?[target].iter().position(|p| p.id == id);
+                    self.board_state.select(position);
+                }
+            }
+            KeyCode::Char('h' | 'p' | 'w' | 'q') => return self.on_projects_key(event),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_todos_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let amount_todos = selected_project(&self.project_list_state, &self.view)?
+            .map_or(0, |project| project.todos.len());
+        let selected = self.todo_list_state.selected();
+        match event.code {
+            KeyCode::Tab => {
+                self.focus = Focus::Links;
+                self.link_list_state.select(Some(0));
+            }
+            KeyCode::Esc => self.focus = Focus::Projects,
+            KeyCode::Down if amount_todos > 0 => {
+                self.todo_list_state.select(Some(selected.map_or(0, |i| (i + 1) % amount_todos)));
+            }
+            KeyCode::Up if amount_todos > 0 => {
+                self.todo_list_state.select(Some(
+                    selected.map_or(0, |i| (i + amount_todos - 1) % amount_todos),
+                ));
+            }
+            KeyCode::Char(' ') | KeyCode::Enter => {
+                if let Some(i) = selected {
+                    update_project_at_index(&self.project_list_state, &self.view, |p| {
+                        if let Some(todo) = p.todos.get_mut(i) {
+                            todo.done = !todo.done;
+                        }
+                    })?;
+                }
+            }
+            KeyCode::Char('d') => {
+                if let Some(i) = selected.filter(|&i| i < amount_todos) {
+                    update_project_at_index(&self.project_list_state, &self.view, |p| {
+                        p.todos.remove(i);
+                    })?;
+                    self.todo_list_state
+                        .select(if amount_todos > 1 { Some(i.saturating_sub(1)) } else { None });
+                }
+            }
+            KeyCode::Char('t') => self.prompt = Some(Prompt::new(PromptKind::Todo, String::new())),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_links_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let links = match selected_project(&self.project_list_state, &self.view)? {
+            Some(project) => project_links(&project)?,
+            None => Vec::new(),
+        };
+        let selected = self.link_list_state.selected();
+        match event.code {
+            KeyCode::Tab if !self.selected_remotes().is_empty() => {
+                self.focus = Focus::Remotes;
+                self.remote_list_state.select(Some(0));
+            }
+            KeyCode::Tab | KeyCode::Esc => self.focus = Focus::Projects,
+            KeyCode::Down if !links.is_empty() => {
+                self.link_list_state.select(Some(selected.map_or(0, |i| (i + 1) % links.len())));
+            }
+            KeyCode::Up if !links.is_empty() => {
+                self.link_list_state.select(Some(
+                    selected.map_or(0, |i| (i + links.len() - 1) % links.len()),
+                ));
+            }
+            KeyCode::Enter => {
+                if let Some((_, target)) = selected.and_then(|i| links.get(i)) {
+                    if select_project_by_id(&mut self.project_list_state, &mut self.view, &target.id)? {
+                        self.link_list_state.select(Some(0));
+                    } else {
+                        self.warning(format!("'{}' isn't in the list", target.name));
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_remotes_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        let remotes = self.selected_remotes();
+        let selected = self.remote_list_state.selected();
+        match event.code {
+            KeyCode::Tab | KeyCode::Esc => self.focus = Focus::Projects,
+            KeyCode::Down if !remotes.is_empty() => {
+                self.remote_list_state.select(Some(selected.map_or(0, |i| (i + 1) % remotes.len())));
+            }
+            KeyCode::Up if !remotes.is_empty() => {
+                self.remote_list_state.select(Some(
+                    selected.map_or(0, |i| (i + remotes.len() - 1) % remotes.len()),
+                ));
+            }
+            KeyCode::Enter | KeyCode::Char('o') => {
+                if let Some(remote) = selected.and_then(|i| remotes.get(i)) {
+                    open_externally(&remote.web_url())?;
+                }
+            }
+            KeyCode::Char('y') => {
+                if let Some(remote) = selected.and_then(|i| remotes.get(i)) {
+                    copy_to_clipboard(&remote.url)?;
+                    self.success(format!("Copied {}", remote.url));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The git remotes of the selected project, read again when the selection moves to
+    /// another directory.
+    fn selected_remotes(&mut self) -> Vec<git::Remote> {
+        let Some(project) = selected_project(&self.project_list_state, &self.view).unwrap_or_default() else {
+            return Vec::new();
+        };
+        if self.remotes.as_ref().is_none_or(|(dir, _)| *dir != project.directory) {
+            self.remotes = Some((project.directory.clone(), git::remotes(&project.directory)));
+        }
+        self.remotes.as_ref().map(|(_, remotes)| remotes.clone()).unwrap_or_default()
+    }
+
+    fn on_projects_key(&mut self, event: KeyEvent) -> Result<(), Error> {
+        match event.code {
+            KeyCode::Char('q') => self.quit = true,
+            KeyCode::Char('h') => self.active_menu_item = MenuItem::Home,
+            KeyCode::Char('p') => self.show_workspace(None),
+            KeyCode::Char(c @ '1'..='9') if event.modifiers.contains(KeyModifiers::ALT) => {
+                let index = c as usize - '1' as usize;
+                if let Some((name, _)) = self.workspaces.get(index) {
+                    self.show_workspace(Some(name.clone()));
+                }
+            }
+            KeyCode::Char(c @ '1'..='9') if self.active_menu_item == MenuItem::Home => {
+                let projects = read_db()?;
+                if let Some(project) = recently_opened(&projects).get(c as usize - '1' as usize) {
+                    self.show_workspace(None);
+                    select_project_by_id(&mut self.project_list_state, &mut self.view, &project.id)?;
+                }
+            }
+            KeyCode::Char(c @ '1'..='9') if self.active_menu_item == MenuItem::Projects => {
+                let mut on_screen = self.list_lines.iter().skip(self.list_offset).filter_map(|line| match line {
+                    ListLine::Row(index) => Some(*index),
+                    ListLine::Header { .. } => None,
+                });
+                if let Some(index) = on_screen.nth(c as usize - '1' as usize) {
+                    self.project_list_state.select(Some(index));
+                }
+            }
+            KeyCode::Char(c) if c.is_alphabetic() && event.modifiers.contains(KeyModifiers::ALT) => {
+                self.active_menu_item = MenuItem::Projects;
+                select_project_by_letter(&mut self.project_list_state, &self.view, c)?;
+            }
+            KeyCode::Char('w') => self.active_menu_item = MenuItem::Week,
+            KeyCode::Char('i') => self.active_menu_item = MenuItem::Timeline,
+            KeyCode::Char('v') => self.active_menu_item = MenuItem::Git,
+            KeyCode::Char('C') => {
+                let index = Theme::NAMES.iter().position(|name| *name == self.config.theme);
+                let name = Theme::NAMES[index.map_or(0, |i| (i + 1) % Theme::NAMES.len())];
+                self.theme = Theme::load(name, &self.config.colors)?;
+                self.config.theme = name.to_string();
+                self.success(format!("Theme: {}", name));
+            }
+            KeyCode::Char('S') => {
+                self.view.group_by = self.view.group_by.next();
+                self.project_list_state.select(Some(0));
+            }
+            KeyCode::Char('E') => self.display.exact_times = !self.display.exact_times,
+            KeyCode::Char('F') => self.display.full_paths = !self.display.full_paths,
+            KeyCode::Char('V') => self.display.vertical_detail = !self.display.vertical_detail,
+            KeyCode::Char('b') => {
+                self.active_menu_item = MenuItem::Board;
+                if self.board_state.selected().is_none() {
+                    self.board_state.select(Some(0));
+                }
+            }
+            KeyCode::Char('a') => self.add_form = Some(AddForm::default()),
+            KeyCode::Char('B') => self.pick_directory(std::env::current_dir().unwrap_or_default()),
+            KeyCode::Char('X') => {
+                let start = std::env::current_dir().unwrap_or_default();
+                self.pick_directory_with(Box::new(picker::Xplr), start);
+            }
+            KeyCode::Enter if self.picker && self.project_list_state.selected().is_some() => self.pick()?,
+            KeyCode::Enter if self.project_list_state.selected().is_some() => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    let Some(mut command) = open_command(&self.config, &project) else {
+                        self.error("The editor command is empty".to_string());
+                        return Ok(());
+                    };
+                    command.current_dir(&project.directory);
+                    mark_opened(&project.id)?;
+                    self.foreground = Some(Foreground::Command(project.name, command));
+                }
+            }
+            KeyCode::Char(' ') | KeyCode::Enter if self.project_list_state.selected().is_none() => {
+                if let Some(key) = self.selected_header.clone() {
+                    self.toggle_group(key);
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    match self.marked.iter().position(|id| *id == project.id) {
+                        Some(index) => {
+                            self.marked.remove(index);
+                        }
+                        None => self.marked.push(project.id),
+                    }
+                    select_next_project(&mut self.project_list_state, &self.view)?;
+                }
+            }
+            KeyCode::Esc => self.marked.clear(),
+            KeyCode::Char('d') => self.delete_selected()?,
+            KeyCode::Char('u') => self.undo_delete()?,
+            KeyCode::Char('z') => {
+                let targets = bulk_targets(&self.marked, &self.project_list_state, &self.view)?;
+                update_projects(&targets, |p| p.status = Status::Archived)?;
+                self.success(format!("Archived {}", count_projects(targets.len())));
+                self.marked.clear();
+            }
+            KeyCode::Char('r') | KeyCode::F(2) => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    self.active_menu_item = MenuItem::Projects;
+                    self.rename = Some(InlineRename::new(&project));
+                }
+            }
+            KeyCode::Char('e') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    self.active_menu_item = MenuItem::Projects;
+                    self.edit_form = Some(EditForm::new(&project));
+                }
+            }
+            KeyCode::Char('G') => {
+                let projects = read_db()?.into_iter().map(|p| (p.name, p.directory)).collect();
+                self.tasks.spawn("Checking repositories for changes", move |cancel| {
+                    scan_git_status(projects, cancel)
+                });
+            }
+            KeyCode::Char('N') => self.start_discovery(),
+            KeyCode::Char('U') => {
+                let projects = read_db()?;
+                for project in &projects {
+                    self.usage.request(&project.directory);
+                }
+                self.success(format!("Measuring {} directories", projects.len()));
+            }
+            KeyCode::Char('m') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    self.open_tmux_session(project)?;
+                }
+            }
+            KeyCode::Char('!') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    if project.is_missing() {
+                        self.warning(format!("The directory of '{}' is gone", project.name));
+                    } else {
+                        mark_opened(&project.id)?;
+                        self.foreground = Some(Foreground::Shell(project.name.clone(), shell_command(&project)));
+                    }
+                }
+            }
+            KeyCode::Char('R') => self.show_readme = !self.show_readme,
+            KeyCode::Char('#') => self.prompt = Some(Prompt::new(PromptKind::Tags, String::new())),
+            KeyCode::Char('W') => self.prompt = Some(Prompt::new(PromptKind::Workspace, String::new())),
+            KeyCode::Char('c') => {
+                update_project_at_index(&self.project_list_state, &self.view, |p| p.status = p.status.next())?;
+            }
+            KeyCode::Char('f') => {
+                self.view.status_filter = next_status_filter(self.view.status_filter);
+                self.project_list_state.select(Some(0));
+            }
+            KeyCode::Char('+') => {
+                update_project_at_index(&self.project_list_state, &self.view, |p| p.priority = p.priority.raise())?;
+            }
+            KeyCode::Char('-') => {
+                update_project_at_index(&self.project_list_state, &self.view, |p| p.priority = p.priority.lower())?;
+            }
+            KeyCode::Char('o') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    match project.repo_url.clone().or_else(|| git::detect_remote_url(&project.directory)) {
+                        Some(url) => open_externally(&git::web_url(&url))?,
+                        None => self.warning(format!("'{}' has no repository URL", project.name)),
+                    }
+                }
+            }
+            KeyCode::Char('O') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    if project.is_missing() {
+                        self.warning(format!("The directory of '{}' is gone", project.name));
+                    } else if self.config.launchers.is_empty() {
+                        mark_opened(&project.id)?;
+                        open_externally(&project.directory)?;
+                    } else {
+                        let mut menu_state = ListState::default();
+                        menu_state.select(Some(0));
+                        self.open_with_menu = Some(menu_state);
+                    }
+                }
+            }
+            KeyCode::Char('y') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    copy_to_clipboard(&project.directory)?;
+                    self.success(format!("Copied {}", project.directory));
+                }
+            }
+            KeyCode::Char('Y') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    copy_to_clipboard(&project.name)?;
+                    self.success(format!("Copied '{}'", project.name));
+                }
+            }
+            KeyCode::Char('A') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    self.prompt = Some(Prompt::new(PromptKind::Alias, project.alias.unwrap_or_default()));
+                }
+            }
+            KeyCode::Char('P') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    let projects = read_db()?;
+                    let parent = project
+                        .parent_id
+                        .and_then(|id| projects.into_iter().find(|p| p.id == id))
+                        .map(|p| p.alias.unwrap_or(p.name))
+                        .unwrap_or_default();
+                    self.prompt = Some(Prompt::new(PromptKind::Parent, parent));
+                }
+            }
+            KeyCode::Char('T') => toggle_timer_at_index(&self.project_list_state, &self.view)?,
+            KeyCode::Char('D') => {
+                if let Some(project) = selected_project(&self.project_list_state, &self.view)? {
+                    let due_date = project.due_date.map(|d| d.to_string()).unwrap_or_default();
+                    self.prompt = Some(Prompt::new(PromptKind::DueDate, due_date));
+                }
+            }
+            KeyCode::Right => {
+                update_project_at_index(&self.project_list_state, &self.view, |p| p.collapsed = false)?;
+            }
+            KeyCode::Left => {
+                update_project_at_index(&self.project_list_state, &self.view, |p| p.collapsed = true)?;
+            }
+            KeyCode::Char('t') if selected_project(&self.project_list_state, &self.view)?.is_some() => {
+                self.prompt = Some(Prompt::new(PromptKind::Todo, String::new()));
+            }
+            KeyCode::Tab => {
+                self.active_menu_item = MenuItem::Projects;
+                self.focus = Focus::Todos;
+                self.todo_list_state.select(Some(0));
+            }
+            KeyCode::Char('l') => {
+                self.prompt = Some(Prompt::new(PromptKind::Link(LinkKind::RelatedTo), String::new()));
+            }
+            KeyCode::Char('L') => {
+                self.prompt = Some(Prompt::new(PromptKind::Link(LinkKind::DependsOn), String::new()));
+            }
+            KeyCode::Char('/') => {
+                self.active_menu_item = MenuItem::Projects;
+                self.prompt = Some(Prompt::new(PromptKind::Search, self.view.search.clone()));
+            }
+            KeyCode::Char('g') => self.prompt = Some(Prompt::new(PromptKind::Jump, String::new())),
+            KeyCode::Char('s') => {
+                let mut menu_state = ListState::default();
+                menu_state.select(SortMode::ALL.iter().position(|mode| *mode == self.view.sort_mode));
+                self.sort_menu = Some(menu_state);
+            }
+            KeyCode::Down if event.modifiers.contains(KeyModifiers::SHIFT) => {
+                move_project_at_index(&mut self.project_list_state, &mut self.view, 1)?;
+            }
+            KeyCode::Up if event.modifiers.contains(KeyModifiers::SHIFT) => {
+                move_project_at_index(&mut self.project_list_state, &mut self.view, -1)?;
+            }
+            KeyCode::Char('J') => move_project_at_index(&mut self.project_list_state, &mut self.view, 1)?,
+            KeyCode::Char('K') => move_project_at_index(&mut self.project_list_state, &mut self.view, -1)?,
+            KeyCode::PageDown => {
+                let page = self.page_size();
+                select_project_by_offset(&mut self.project_list_state, &self.view, page)?;
+            }
+            KeyCode::PageUp => {
+                let page = self.page_size();
+                select_project_by_offset(&mut self.project_list_state, &self.view, -page)?;
+            }
+            KeyCode::Home => select_project_by_offset(&mut self.project_list_state, &self.view, isize::MIN)?,
+            KeyCode::End => select_project_by_offset(&mut self.project_list_state, &self.view, isize::MAX)?,
+            KeyCode::Down | KeyCode::Up if self.view.group_by != GroupBy::Nothing => {
+                self.move_line(event.code == KeyCode::Down)
+            }
+            KeyCode::Down => select_next_project(&mut self.project_list_state, &self.view)?,
+            KeyCode::Up => select_previous_project(&mut self.project_list_state, &self.view)?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn on_mouse(&mut self, mouse: MouseEvent) -> Result<(), Error> {
+        // Mouse input only applies when no popup or prompt is capturing the keyboard.
+        let popup_open = self.add_form.is_some()
+            || self.edit_form.is_some()
+            || self.dir_browser.is_some()
+            || self.discovered.is_some()
+            || self.task_list.is_some();
+        if self.prompt.is_some() || self.sort_menu.is_some() || self.open_with_menu.is_some() || popup_open {
+            return Ok(());
+        }
+        match mouse.kind {
+            MouseEventKind::ScrollDown if self.active_menu_item == MenuItem::Projects => {
+                select_next_project(&mut self.project_list_state, &self.view)?
+            }
+            MouseEventKind::ScrollUp if self.active_menu_item == MenuItem::Projects => {
+                select_previous_project(&mut self.project_list_state, &self.view)?
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                if contains(self.tabs_area, mouse.column, mouse.row) {
+                    let tabs = self.tabs();
+                    let titles: Vec<&str> = tabs.iter().map(|(_, title)| title.as_str()).collect();
+                    let clicked = clicked_tab(&titles, self.tabs_area, mouse.column).map(|i| tabs[i].0.clone());
+                    match clicked {
+                        Some(Tab::Home) => self.active_menu_item = MenuItem::Home,
+                        Some(Tab::Projects) => self.show_workspace(None),
+                        Some(Tab::Workspace(name)) => self.show_workspace(Some(name)),
+                        Some(Tab::Board) => self.active_menu_item = MenuItem::Board,
+                        Some(Tab::Week) => self.active_menu_item = MenuItem::Week,
+                        Some(Tab::Timeline) => self.active_menu_item = MenuItem::Timeline,
+                        Some(Tab::Git) => self.active_menu_item = MenuItem::Git,
+                        Some(Tab::Add) => self.add_form = Some(AddForm::default()),
+                        Some(Tab::Delete) => self.delete_selected()?,
+                        Some(Tab::Quit) => self.quit = true,
+                        None => {}
+                    }
+                } else if contains(self.list_area, mouse.column, mouse.row) && mouse.row > self.list_area.y {
+                    let line = self.list_offset + (mouse.row - self.list_area.y - 1) as usize;
+                    if let Some(line) = self.list_lines.get(line).cloned() {
+                        self.select_line(&line);
+                        self.focus = Focus::Projects;
+                        if let ListLine::Header { key, .. } = line {
+                            self.toggle_group(key);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Deletes the marked projects, or the selected one if none is marked.
+    fn delete_selected(&mut self) -> Result<(), Error> {
+        let targets = bulk_targets(&self.marked, &self.project_list_state, &self.view)?;
+        let mut deleted = Vec::new();
+        for id in &targets {
+            deleted.extend(remove_project(id)?);
+        }
+        let message = match deleted.as_slice() {
+            [] => return Ok(()),
+            [project] => format!("Deleted '{}' (u to undo)", project.project.name),
+            projects => format!("Deleted {} (u to undo)", count_projects(projects.len())),
+        };
+        self.success(message);
+        self.deleted = deleted;
+        self.marked.clear();
+        let selected = self.project_list_state.selected().unwrap_or(0);
+        self.project_list_state.select(Some(selected.saturating_sub(1)));
+        Ok(())
+    }
+
+    fn undo_delete(&mut self) -> Result<(), Error> {
+        let Some(first) = self.deleted.first() else {
+            self.warning("Nothing to undo".to_string());
+            return Ok(());
+        };
+        let id = first.project.id.clone();
+        let amount = self.deleted.len();
+        // Restore in reverse so every project goes back to the position it was removed from.
+        for deleted in self.deleted.drain(..).rev() {
+            restore_project(deleted)?;
+        }
+        select_project_by_id(&mut self.project_list_state, &mut self.view, &id)?;
+        self.success(format!("Restored {}", count_projects(amount)));
+        Ok(())
+    }
+}
+
+/// The dashboard on the Home tab, built from the database each time it's drawn.
+fn draw_home<B: Backend>(rect: &mut Frame<B>, area: Rect, theme: &Theme) {
+    let projects = read_db().unwrap_or_default();
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(3)].as_ref())
+        .split(area);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(vec![Constraint::Ratio(1, 4); 4])
+        .split(chunks[1]);
+
+    rect.render_widget(render_overview(&projects, theme), chunks[0]);
+
+    let tags = tag_counts(&projects)
+        .into_iter()
+        .map(|(tag, count)| ListItem::new(format!("{:>3}  {}", count, tag)))
+        .collect();
+    rect.render_widget(render_dashboard_list("Tags", tags, theme), columns[0]);
+
+    let mut added: Vec<&Project> = projects.iter().collect();
+    added.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+    let added = added
+        .iter()
+        .take(5)
+        .map(|p| {
+            ListItem::new(Spans::from(vec![
+                Span::raw(p.name.clone()),
+                Span::styled(format!("  {}", relative_time(p.created_at)), theme.muted()),
+            ]))
+        })
+        .collect();
+    rect.render_widget(render_dashboard_list("Recently added", added, theme), columns[1]);
+
+    let opened = recently_opened(&projects)
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", i + 1), Style::default().fg(theme.accent)),
+                Span::raw(p.name.clone()),
+                Span::styled(format!("  {}", relative_time(p.last_opened_at.unwrap_or_default())), theme.muted()),
+            ]))
+        })
+        .collect();
+    let title = format!("Recently opened (1-{} to jump)", RECENT_PROJECTS);
+    rect.render_widget(render_dashboard_list(&title, opened, theme), columns[2]);
+
+    let missing = projects
+        .iter()
+        .filter(|p| p.is_missing())
+        .map(|p| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(p.name.clone(), theme.error()),
+                Span::styled(format!("  {}", p.directory), theme.muted()),
+            ]))
+        })
+        .collect();
+    rect.render_widget(render_dashboard_list("Missing directories", missing, theme), columns[3]);
+}
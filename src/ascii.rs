@@ -0,0 +1,41 @@
+use tui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+/// Replaces the box-drawing characters and symbols the UI draws with plain ASCII, for
+/// terminals that can't show them. Rendered over the whole frame after everything else.
+/// Text from the database, like project names, is left alone.
+pub struct AsciiOnly;
+
+impl Widget for AsciiOnly {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let cell = buf.get_mut(x, y);
+                if let Some(replacement) = cell.symbol.chars().next().and_then(replacement) {
+                    cell.set_char(replacement);
+                }
+            }
+        }
+    }
+}
+
+fn replacement(symbol: char) -> Option<char> {
+    let replacement = match symbol {
+        '─' | '━' | '═' => '-',
+        '│' | '┃' | '║' => '|',
+        '┌' | '┐' | '└' | '┘' | '╭' | '╮' | '╰' | '╯' | '├' | '┤' | '┬' | '┴' | '┼' | '╔' | '╗' | '╚' | '╝' => '+',
+        '█' | '■' => '#',
+        '●' | '•' => '*',
+        '▸' | '→' => '>',
+        '▾' | '↓' => 'v',
+        '←' => '<',
+        '↑' => '^',
+        '…' => '.',
+        '✎' => '>',
+        '⏱' => 'T',
+        '⚠' => '!',
+        // The spinner of background tasks.
+        '⠋' | '⠙' | '⠹' | '⠸' | '⠼' | '⠴' | '⠦' | '⠧' | '⠇' | '⠏' => '*',
+        _ => return None,
+    };
+    Some(replacement)
+}
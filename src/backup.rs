@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use crate::{db_file, get_db_path, is_read_only, Error, Project};
+
+/// How the time a backup was made is written in its file name, in local time.
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// A copy of the DB in the `backups` directory next to it, named like `db-20240131-184500.json`.
+pub struct Backup {
+    pub path: PathBuf,
+    pub created_at: DateTime<Local>,
+    /// Made by `restore` to undo it, rather than asked for.
+    pub before_restore: bool,
+}
+
+impl Backup {
+    pub fn file_name(&self) -> String {
+        self.path.file_name().unwrap_or_default().to_string_lossy().to_string()
+    }
+
+    /// The projects in the backup, which also makes sure it can be restored.
+    pub fn read(&self) -> Result<Vec<Project>, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(&self.path)?)?)
+    }
+
+    fn parse(path: PathBuf) -> Option<Backup> {
+        let name = path.file_name()?.to_str()?;
+        let stem = name.strip_prefix("db-")?.strip_suffix(".json")?;
+        let (timestamp, before_restore) = match stem.strip_suffix("-before-restore") {
+            Some(timestamp) => (timestamp, true),
+            None => (stem, false),
+        };
+        let created_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+        let created_at = Local.from_local_datetime(&created_at).earliest()?;
+        Some(Backup { path, created_at, before_restore })
+    }
+}
+
+fn backups_dir() -> PathBuf {
+    db_file().parent().unwrap_or(Path::new("")).join("backups")
+}
+
+/// Copies the DB into the backups directory.
+pub fn create() -> Result<Backup, Error> {
+    copy_db("")
+}
+
+fn copy_db(suffix: &str) -> Result<Backup, Error> {
+    let created_at = Local::now();
+    let dir = backups_dir();
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("db-{}{}.json", created_at.format(TIMESTAMP_FORMAT), suffix));
+    fs::copy(get_db_path()?, &path)?;
+    Ok(Backup { path, created_at, before_restore: !suffix.is_empty() })
+}
+
+/// The backups there are, newest first. Other files in the directory are ignored.
+pub fn list() -> Result<Vec<Backup>, Error> {
+    let dir = backups_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<Backup> = fs::read_dir(dir)?
+        .flatten()
+        .filter_map(|entry| Backup::parse(entry.path()))
+        .collect();
+    backups.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(a.before_restore.cmp(&b.before_restore)));
+    Ok(backups)
+}
+
+/// Replaces the DB with `backup`, after backing up the current one, which is returned.
+/// A backup that doesn't parse is refused and leaves the DB alone.
+pub fn restore(backup: &Backup) -> Result<Backup, Error> {
+    if is_read_only() {
+        return Err(Error::ReadOnly);
+    }
+    backup.read()?;
+    let safety_copy = copy_db("-before-restore")?;
+    fs::copy(&backup.path, get_db_path()?)?;
+    Ok(safety_copy)
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use tui::{
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState},
+};
+
+use crate::theme::Theme;
+
+/// A minimal directory browser: Enter descends, Backspace goes up, Space selects.
+pub struct DirBrowser {
+    pub current: PathBuf,
+    entries: Vec<PathBuf>,
+    pub state: ListState,
+    show_hidden: bool,
+}
+
+impl DirBrowser {
+    pub fn new(start: &Path) -> DirBrowser {
+        let start = if start.is_dir() {
+            start.to_path_buf()
+        } else {
+            dirs::home_dir().unwrap_or_else(|| PathBuf::from("/"))
+        };
+        let mut browser = DirBrowser {
+            current: start,
+            entries: Vec::new(),
+            state: ListState::default(),
+            show_hidden: false,
+        };
+        browser.refresh();
+        browser
+    }
+
+    /// Re-reads the sub-directories of the current directory, sorted by name.
+    fn refresh(&mut self) {
+        self.entries = fs::read_dir(&self.current)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.path())
+                    .filter(|path| path.is_dir())
+                    .filter(|path| self.show_hidden || !is_hidden(path))
+                    .collect()
+            })
+            .unwrap_or_default();
+        self.entries.sort();
+        self.state.select(if self.entries.is_empty() { None } else { Some(0) });
+    }
+
+    pub fn next(&mut self) {
+        if let Some(selected) = self.state.selected() {
+            self.state.select(Some((selected + 1) % self.entries.len()));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if let Some(selected) = self.state.selected() {
+            let len = self.entries.len();
+            self.state.select(Some((selected + len - 1) % len));
+        }
+    }
+
+    pub fn descend(&mut self) {
+        if let Some(path) = self.highlighted() {
+            self.current = path.to_path_buf();
+            self.refresh();
+        }
+    }
+
+    pub fn ascend(&mut self) {
+        let child = self.current.clone();
+        if let Some(parent) = child.parent() {
+            self.current = parent.to_path_buf();
+            self.refresh();
+            // Keep the directory we came from highlighted.
+            if let Some(index) = self.entries.iter().position(|path| *path == child) {
+                self.state.select(Some(index));
+            }
+        }
+    }
+
+    pub fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        self.refresh();
+    }
+
+    pub fn highlighted(&self) -> Option<&Path> {
+        self.state
+            .selected()
+            .and_then(|selected| self.entries.get(selected))
+            .map(PathBuf::as_path)
+    }
+
+    pub fn render<'a>(&self, theme: &Theme) -> List<'a> {
+        let items: Vec<_> = self
+            .entries
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                ListItem::new(Spans::from(vec![Span::raw(format!("{}/", name))]))
+            })
+            .collect();
+
+        List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(theme.text())
+                    .border_style(theme.border(false))
+                    .title(format!(
+                        "{} (Enter open, Backspace up, Space select, . hidden, Esc cancel)",
+                        self.current.display()
+                    ))
+                    .border_type(BorderType::Plain),
+            )
+            .highlight_style(theme.selection())
+    }
+}
+
+/// Top-level entries of `directory` for the preview pane: directories first, each
+/// with a trailing `/`, then files, both sorted by name. Hidden entries are included.
+pub fn preview(directory: &Path) -> io::Result<Vec<String>> {
+    let mut dirs = Vec::new();
+    let mut files = Vec::new();
+    for entry in fs::read_dir(directory)?.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.path().is_dir() {
+            dirs.push(format!("{}/", name));
+        } else {
+            files.push(name);
+        }
+    }
+    dirs.sort();
+    files.sort();
+    dirs.extend(files);
+    Ok(dirs)
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
@@ -0,0 +1,2150 @@
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, Utc};
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
+use serde_json::{json, Value};
+
+use crate::backup;
+use crate::git::{self, GitStatus};
+use crate::config;
+use crate::discover::{self, looks_like_project};
+use crate::export;
+use crate::forge;
+use crate::hooks;
+use crate::notify;
+use crate::search;
+use crate::tmux;
+use crate::usage::format_size;
+use crate::{
+    add_directories, count_projects, open_command, open_externally, script_command, shell_command, set_fresh, set_portable, set_read_only, expand_tilde, format_duration, find_project, is_read_only, mark_opened, new_project, read_db, relative_time, remove_project, run_ui, sort_projects, tag_counts, update_projects, write_db,
+    AddForm, Error, Project, SortMode, Status,
+};
+
+const USAGE: &str = "\
+Usage: whisk [COMMAND]
+
+Without a command, whisk starts the interactive UI.
+
+Commands:
+  path <project>    Print the directory of a project, e.g. cd \"$(whisk path api)\"
+  open <project>    Open a project with its own command, set as Open with when editing
+                    it in the UI, or the editor of the config, or $VISUAL / $EDITOR
+  reveal <project>  Open the directory of a project in the file manager
+  browse <project>  Open the repository of a project in the browser, at its web address
+                    for SSH remotes
+  shell <project>   Start $SHELL in the directory of a project, with its environment variables
+  tmux <project>    Switch to a tmux session named after a project, or attach to it
+                    outside tmux, starting it in the project's directory if there's none
+  due [--within N]  List upcoming deadlines, optionally only those due in N days
+  list [OPTIONS]    Print the projects as a table
+  stats             Summarize the projects: statuses, tags, the oldest, newest and most
+                    opened ones, and the time tracked
+  add <path> [--name NAME]
+                    Add a directory as a project, named after the directory unless
+                    NAME is given. `whisk add .` adds the current directory.
+  add --stdin       Add the directories read from stdin, one per line, e.g.
+                    find ~/dev -maxdepth 2 -name .git | whisk add --stdin
+  run <project> [task]
+                    Run a task of the project in its directory, with its environment
+                    variables, and exit with the task's exit code. Lists the tasks
+                    without a task name.
+  clone <url> [path] [--name NAME]
+                    Clone a repository into path, or into the clone_root of the config
+                    (the first of project_roots by default) under its name, and add it
+                    as a project named after the repository unless NAME is given
+  discover [ROOT].. [--depth N] [--yes]
+                    Add the projects found in the roots, or the project_roots of the
+                    config, and up to N (3) levels below them, after showing them
+  watch [ROOT].. [--depth N] [--interval SECS]
+                    Keep watching the roots like discover looks through them, every
+                    SECS (5) seconds until stopped with Ctrl-C. Projects created there
+                    are added right away, and those whose directory is removed are
+                    reported, to be dealt with by prune.
+  import <zoxide|autojump|jetbrains> [--yes]
+                    Add the projects among the directories zoxide or autojump know, or
+                    JetBrains IDEs like IntelliJ IDEA, CLion and RustRover opened
+                    lately, after showing them, or right away with --yes
+  import <github|gitlab|gitea> --user NAME [--url URL] [--dir DIR] [--ssh] [--yes]
+                    List the repositories of NAME on GitHub, GitLab or a Gitea site
+                    that aren't projects yet and clone the chosen ones, or all with
+                    --yes, into DIR or the clone_root of the config, over SSH with
+                    --ssh. Those there already are only added. Projects keep the URL
+                    and description of their repository. URL is the address of a
+                    self-hosted site, which Gitea needs
+  prune [--archive] [--yes]
+                    Remove the projects whose directories are gone, after listing
+                    them, or archive them instead with --archive
+  tag add <project> <tag>...
+  tag remove <project> <tag>...
+                    Add tags to a project or remove them from it
+  tag list [project]
+                    List all tags with how many projects have them, or the tags of
+                    a project
+  rename <project> <name>
+                    Give a project a new name, which no other project may have
+  mv <project> <path> [--relocate]
+                    Point a project at the directory it was moved to, or move the
+                    directory there as well with --relocate
+  exec (--all | FILTERS) [--jobs N] -- <command> [args]
+                    Run a command in the directory of every project, or of those
+                    matching the filters of list, and report which ones failed.
+                    With --jobs, N projects run at a time and the output of each
+                    is shown once it's done
+  grep <pattern> [FILTERS] [--glob GLOB].. [-i]
+                    Search the files of every project, or of those matching the
+                    filters of list, for lines matching a regular expression. --glob
+                    limits the search to file names like '*.rs', -i ignores case.
+                    Hidden files and build directories are skipped.
+  export <tmuxinator|tmuxp> [FILTERS] [--dir DIR] [--force]
+                    Write a tmuxinator or tmuxp config for every project, or those
+                    matching the filters of list, to ~/.config/tmuxinator or ~/.tmuxp
+                    or DIR. The sessions have the tmux_windows of the config, or a
+                    shell. Existing configs are only overwritten with --force.
+  git status [FILTERS]
+                    Show the branch, changed files and unpushed or unpulled commits of
+                    every repository, or of those matching the filters of list, the
+                    ones that need attention first
+  backup [--list]   Save a copy of the projects to ~/.config/whisk/backups, or list the
+                    copies there with --list
+  restore [BACKUP] [--yes]
+                    Replace the projects with a backup, by its number in the list or
+                    its file name, after asking. The current projects are backed up
+                    first. Lists the backups without BACKUP.
+  pick [--field F] [--sort MODE]
+                    Search for a project in the UI and print its directory, or its name,
+                    id or alias with --field. Exits with 130 if none was chosen. MODE is
+                    a sort of list, only for this run, like frecency.
+  resolve [SELECTION] [--field F]
+                    Print the directory, or another field like pick, of the project
+                    on a line of `list --format dmenu`, read from stdin without
+                    SELECTION, e.g. whisk list --format dmenu | rofi -dmenu | whisk resolve
+  init <shell> [--bind KEY]
+                    Print a `whisk cd` shell function for bash, zsh or fish, to add to
+                    the shell's startup file, e.g. eval \"$(whisk init bash)\". With
+                    --bind, KEY like ^p or alt-p opens the picker, projects opened
+                    often and lately first, and changes into the chosen one
+  rm <project> [--force]
+                    Remove a project after asking, or right away with --force.
+                    The directory itself is left alone.
+
+Options:
+  --json            Print machine-readable JSON instead, for path, due, list, stats,
+                    git status and tag list
+  --quiet           Leave out messages about what was done, like \"Added 'api'\"
+  --config FILE     Read the config from FILE instead of ~/.config/whisk/config.json,
+                    also for the UI, e.g. whisk --config demo.json
+  --portable        Keep the projects, config and UI state next to the whisk executable
+                    instead of in ~/.config/whisk, e.g. on a USB stick. A file named
+                    whisk-portable there does the same.
+  --dry-run         Print what would change instead of changing it, for rm, prune,
+                    import, discover, restore, tag add, tag remove and export
+  --read-only       Refuse to add, change or remove projects, in the UI as well, e.g.
+                    to browse someone else's projects
+  --fresh           Start the UI on the home tab with no filters, rather than where it
+                    was left off
+
+Options of list:
+  --columns A,B,..  Columns to print: id, name, alias, status, priority, directory,
+                    tags, workspace, due, created, opened, size (default: id,name,status,directory,
+                    or all of them with --json)
+  --format dmenu    One project per line as its name and directory separated by a tab,
+                    for launchers like dmenu and rofi
+  --sort MODE       date-added, name, last-opened, path, priority, manual, due-date, size
+                    or frecency: opened often and lately first
+  --status STATUS   Only projects with this status, like active or on-hold
+  --tag TAG         Only projects with this tag
+  --workspace NAME  Only projects in this workspace
+
+A project can be referenced by its alias, ID, short ID or name. `path`, `open`, `reveal`,
+`browse`, `shell` and `tmux` also take part of a name or alias when it matches a single project best.
+
+Environment:
+  WHISK_DB          The DB file to use instead of ~/.config/whisk/db.json. Backups go
+                    into a backups directory next to it.
+  WHISK_CONFIG      The config file to use instead of ~/.config/whisk/config.json,
+                    unless --config is given
+  GITHUB_TOKEN      The token for `import github`, instead of the github_token of the
+  GITLAB_TOKEN      config, and likewise for GitLab and Gitea. Private repositories
+  GITEA_TOKEN       are only listed with one
+
+Exit codes:
+  0    Success
+  1    A project, task or backup wasn't found, or the command failed
+  2    The DB couldn't be read or written
+  64   Invalid arguments
+  130  Cancelled: the picker was quit, a question answered with no or an
+       empty selection given to resolve
+`run` exits with the code of the task instead.";
+
+/// Exit codes, see the end of `USAGE`.
+const EXIT_FAILURE: i32 = 1;
+const EXIT_DB_ERROR: i32 = 2;
+const EXIT_USAGE: i32 = 64;
+const EXIT_CANCELLED: i32 = 130;
+
+/// Set by `--quiet`.
+static QUIET: AtomicBool = AtomicBool::new(false);
+/// Set by `--dry-run`: commands print what they would change instead of changing it.
+static DRY_RUN: AtomicBool = AtomicBool::new(false);
+
+/// Like `println!`, for messages about what was done rather than what was asked for,
+/// which `--quiet` leaves out.
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if !QUIET.load(Ordering::Relaxed) {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// How long `whisk watch` waits between looks through the roots.
+const WATCH_INTERVAL: Duration = Duration::from_secs(5);
+
+const DEFAULT_COLUMNS: &str = "id,name,status,directory";
+const COLUMNS: [&str; 12] = [
+    "id", "name", "alias", "status", "priority", "directory", "tags", "workspace", "due", "created", "opened", "size",
+];
+
+/// The commands that take `--json`.
+const JSON_COMMANDS: [&str; 6] = ["path", "due", "list", "git", "stats", "tag"];
+
+/// The commands that take `--dry-run`.
+const DRY_RUN_COMMANDS: [&str; 7] = ["rm", "prune", "import", "discover", "restore", "tag", "export"];
+
+/// The commands that add, change or remove projects, besides `tag add` and `tag remove`.
+const CHANGING_COMMANDS: [&str; 10] = ["add", "clone", "discover", "watch", "import", "prune", "rename", "mv", "restore", "rm"];
+
+fn dry_run() -> bool {
+    DRY_RUN.load(Ordering::Relaxed)
+}
+
+/// Runs the command in `args` and exits with one of the exit codes above on errors.
+pub fn run(args: &[String]) {
+    // Options after `--` belong to the command `exec` runs.
+    let end = args.iter().position(|arg| arg == "--").unwrap_or(args.len());
+    let json = args[..end].iter().any(|arg| arg == "--json");
+    QUIET.store(args[..end].iter().any(|arg| arg == "--quiet"), Ordering::Relaxed);
+    DRY_RUN.store(args[..end].iter().any(|arg| arg == "--dry-run"), Ordering::Relaxed);
+    let mut options = Vec::new();
+    let mut config_file = None;
+    let mut portable = false;
+    let mut read_only = false;
+    let mut fresh = false;
+    let mut rest = args[..end].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--json" | "--quiet" | "--dry-run" => {}
+            "--portable" => portable = true,
+            "--read-only" => read_only = true,
+            "--fresh" => fresh = true,
+            "--config" => match rest.next() {
+                Some(path) => config_file = Some(path.clone()),
+                None => usage_error("--config expects a file"),
+            },
+            _ => match arg.strip_prefix("--config=") {
+                Some(path) => config_file = Some(path.to_string()),
+                None => options.push(arg.clone()),
+            },
+        }
+    }
+    let args: Vec<String> = options.into_iter().chain(args[end..].iter().cloned()).collect();
+
+    if portable {
+        set_portable();
+    }
+    if read_only {
+        set_read_only();
+    }
+    if fresh {
+        set_fresh();
+    }
+    if let Some(path) = &config_file {
+        let path = expand_tilde(path);
+        if !path.is_file() {
+            usage_error(&format!("there's no config file '{}'", path.display()));
+        }
+        config::set_path(path);
+    }
+
+    // With nothing but these options, it's the UI that should use them.
+    let result = if args.is_empty() && (config_file.is_some() || portable || read_only || fresh) {
+        run_ui(false, None).map(|_| ())
+    } else {
+        run_command(&args, json)
+    };
+    if let Err(err) = result {
+        eprintln!("error: {}", err);
+        let code = match err.downcast_ref::<Error>() {
+            Some(Error::ReadDBError(_) | Error::ParseDBError(_)) => EXIT_DB_ERROR,
+            _ => EXIT_FAILURE,
+        };
+        process::exit(code);
+    }
+}
+
+fn run_command(args: &[String], json: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(command) = args.first() else {
+        usage_error("missing command");
+    };
+    if json && !JSON_COMMANDS.contains(&command.as_str()) {
+        usage_error(&format!("'{}' has no JSON output", command));
+    }
+    if dry_run() && !DRY_RUN_COMMANDS.contains(&command.as_str()) {
+        usage_error(&format!("'{}' has no dry run", command));
+    }
+    // Refused before anything happens, like cloning or moving a directory, rather than
+    // once the DB is written.
+    let changes_projects = CHANGING_COMMANDS.contains(&command.as_str())
+        || (command == "tag" && matches!(args.get(1).map(String::as_str), Some("add" | "remove")));
+    if is_read_only() && changes_projects && !dry_run() {
+        return Err(Error::ReadOnly.into());
+    }
+
+    match command.as_str() {
+        "path" => {
+            let project = resolve_fuzzy(args.get(1))?;
+            mark_opened(&project.id)?;
+            if json {
+                print_json(&json!({ "id": project.id, "name": project.name, "directory": project.directory }));
+            } else {
+                println!("{}", project.directory);
+            }
+        }
+        "open" => {
+            let project = resolve_fuzzy(args.get(1))?;
+            mark_opened(&project.id)?;
+            open_in_editor(&project)?;
+        }
+        "reveal" => {
+            let project = resolve_fuzzy(args.get(1))?;
+            if project.is_missing() {
+                return Err(Error::NotADirectory(project.directory).into());
+            }
+            mark_opened(&project.id)?;
+            open_externally(&project.directory)?;
+        }
+        "browse" => {
+            let project = resolve_fuzzy(args.get(1))?;
+            let Some(url) = project.repo_url.clone().or_else(|| git::detect_remote_url(&project.directory)) else {
+                eprintln!("error: '{}' has no repository URL", project.name);
+                process::exit(EXIT_FAILURE);
+            };
+            open_externally(&git::web_url(&url))?;
+        }
+        "shell" => {
+            let project = resolve_fuzzy(args.get(1))?;
+            if project.is_missing() {
+                return Err(Error::NotADirectory(project.directory).into());
+            }
+            mark_opened(&project.id)?;
+            // The exit status is the last command's in the shell, not whether it worked.
+            shell_command(&project).status().map_err(|err| format!("can't run a shell: {}", err))?;
+        }
+        "tmux" => {
+            let project = resolve_fuzzy(args.get(1))?;
+            mark_opened(&project.id)?;
+            let name = tmux::session_name(&project.name);
+            if tmux::inside() {
+                tmux::switch(&name, &project.directory)?;
+            } else {
+                let status = tmux::attach_command(&name, &project.directory)
+                    .status()
+                    .map_err(|err| format!("can't run tmux: {}", err))?;
+                if !status.success() {
+                    process::exit(status.code().unwrap_or(EXIT_FAILURE));
+                }
+            }
+        }
+        "due" => {
+            let within = match args.get(1).map(String::as_str) {
+                Some("--within") => match args.get(2).and_then(|days| days.parse::<i64>().ok()) {
+                    Some(days) => Some(days),
+                    None => usage_error("--within expects a number of days"),
+                },
+                Some(other) => usage_error(&format!("unexpected argument '{}'", other)),
+                None => None,
+            };
+            list_due(within, json)?;
+        }
+        "list" => list(&args[1..], json)?,
+        "stats" => stats(json)?,
+        "add" => add(&args[1..])?,
+        "run" => {
+            let project = resolve_fuzzy(args.get(1))?;
+            match args.get(2) {
+                Some(task) => run_task(&project, task)?,
+                None => {
+                    for (name, command) in &project.tasks {
+                        println!("{}  {}", name, command);
+                    }
+                }
+            }
+        }
+        "clone" => {
+            let mut name = None;
+            let mut positional = Vec::new();
+            let mut rest = args[1..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--name" => match rest.next() {
+                        Some(value) => name = Some(value.clone()),
+                        None => usage_error("--name expects a value"),
+                    },
+                    other if other.starts_with('-') => usage_error(&format!("unexpected argument '{}'", other)),
+                    _ => positional.push(arg.clone()),
+                }
+            }
+            let Some(url) = positional.first() else {
+                usage_error("missing URL argument");
+            };
+            if positional.len() > 2 {
+                usage_error(&format!("unexpected argument '{}'", positional[2]));
+            }
+            clone(url, positional.get(1), name)?;
+        }
+        "discover" => {
+            let mut depth = discover::DEFAULT_DEPTH;
+            let mut yes = false;
+            let mut roots = Vec::new();
+            let mut rest = args[1..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--yes" | "-y" => yes = true,
+                    "--depth" => match rest.next().and_then(|depth| depth.parse().ok()) {
+                        Some(n) => depth = n,
+                        None => usage_error("--depth expects a number"),
+                    },
+                    other if other.starts_with('-') => usage_error(&format!("unexpected argument '{}'", other)),
+                    root => roots.push(root.to_string()),
+                }
+            }
+            let directories = root_directories(roots)?;
+            let started = Instant::now();
+            let found = discover::discover(&directories, depth, || false);
+            // The list to choose from waits for the user, who may have looked away.
+            notify::task_done(started.elapsed(), "whisk discover", "Finished looking for projects");
+            import(found, yes)?;
+        }
+        "watch" => {
+            let mut depth = discover::DEFAULT_DEPTH;
+            let mut interval = WATCH_INTERVAL;
+            let mut roots = Vec::new();
+            let mut rest = args[1..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--depth" => match rest.next().and_then(|depth| depth.parse().ok()) {
+                        Some(n) => depth = n,
+                        None => usage_error("--depth expects a number"),
+                    },
+                    "--interval" => match rest.next().and_then(|secs| secs.parse().ok()) {
+                        Some(secs) if secs > 0 => interval = Duration::from_secs(secs),
+                        _ => usage_error("--interval expects a positive number of seconds"),
+                    },
+                    other if other.starts_with('-') => usage_error(&format!("unexpected argument '{}'", other)),
+                    root => roots.push(root.to_string()),
+                }
+            }
+            watch(&root_directories(roots)?, depth, interval)?;
+        }
+        "import" => {
+            // Without a source there's nothing after it.
+            let options = args.get(2..).unwrap_or_default();
+            let yes = options.iter().any(|arg| arg == "--yes" || arg == "-y");
+            let directories = match args.get(1).map(String::as_str) {
+                Some("zoxide") => zoxide_directories(),
+                Some("autojump") => autojump_directories(),
+                Some("jetbrains") => jetbrains_directories(),
+                Some(source) if forge::NAMES.contains(&source) => return import_from_forge(source, options, yes),
+                Some(other) => usage_error(&format!(
+                    "can't import from '{}', only zoxide, autojump, jetbrains, github, gitlab or gitea",
+                    other
+                )),
+                None => usage_error("missing source argument"),
+            };
+            import(directories?, yes)?;
+        }
+        "prune" => {
+            let archive = args[1..].iter().any(|arg| arg == "--archive");
+            let yes = args[1..].iter().any(|arg| arg == "--yes" || arg == "-y");
+            prune(archive, yes)?;
+        }
+        "tag" => tag(&args[1..], json)?,
+        "rename" => {
+            let project = resolve(args.get(1))?;
+            let Some(name) = args.get(2).map(|name| name.trim()) else {
+                usage_error("missing new name argument");
+            };
+            rename(&project, name)?;
+            info!("Renamed '{}' to '{}'", project.name, name);
+        }
+        "mv" => {
+            let relocate = args[1..].iter().any(|arg| arg == "--relocate");
+            let mut positional = args[1..].iter().filter(|arg| !arg.starts_with("--"));
+            let project = resolve(positional.next())?;
+            let Some(target) = positional.next() else {
+                usage_error("missing path argument");
+            };
+            move_project(&project, target, relocate)?;
+        }
+        "exec" => exec(&args[1..])?,
+        "grep" => grep(&args[1..])?,
+        "export" => export(&args[1..])?,
+        "git" => match args.get(1).map(String::as_str) {
+            Some("status") => git_status(&args[2..], json)?,
+            Some(other) => usage_error(&format!("unknown git command '{}', only status", other)),
+            None => usage_error("missing git command"),
+        },
+        "backup" => match args.get(1).map(String::as_str) {
+            Some("--list") => list_backups()?,
+            Some(other) => usage_error(&format!("unexpected argument '{}'", other)),
+            None => {
+                let backup = backup::create()?;
+                info!("Backed up {} to {}", count_projects(backup.read()?.len()), backup.path.display());
+            }
+        },
+        "restore" => {
+            let yes = args[1..].iter().any(|arg| arg == "--yes" || arg == "-y");
+            match args[1..].iter().find(|arg| !arg.starts_with('-')) {
+                Some(choice) => restore(choice, yes)?,
+                None => list_backups()?,
+            }
+        }
+        "pick" => {
+            let mut field = "path";
+            let mut sort = None;
+            let mut rest = args[1..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--field" => field = parse_field(rest.next()),
+                    "--sort" => match rest.next().and_then(|value| parse_choice(&SortMode::ALL, SortMode::label, value)) {
+                        Some(mode) => sort = Some(mode),
+                        None => usage_error("--sort expects a sort mode of list, like frecency"),
+                    },
+                    other => usage_error(&format!("unexpected argument '{}'", other)),
+                }
+            }
+            match run_ui(true, sort)? {
+                Some(project) => println!("{}", field_value(&project, field)),
+                None => process::exit(EXIT_CANCELLED),
+            }
+        }
+        "resolve" => {
+            let mut field = "path";
+            let mut selection = None;
+            let mut rest = args[1..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "--field" => field = parse_field(rest.next()),
+                    _ if selection.is_none() => selection = Some(arg.clone()),
+                    other => usage_error(&format!("unexpected argument '{}'", other)),
+                }
+            }
+            let selection = match selection {
+                Some(selection) => selection,
+                None => io::stdin().lines().next().transpose()?.unwrap_or_default(),
+            };
+            // What a launcher prints when it's closed without a choice.
+            if selection.trim().is_empty() {
+                process::exit(EXIT_CANCELLED);
+            }
+            let project = resolve_selection(&selection)?;
+            mark_opened(&project.id)?;
+            println!("{}", field_value(&project, field));
+        }
+        "init" => {
+            let shell = match args.get(1).map(String::as_str) {
+                Some(shell @ ("bash" | "zsh" | "fish")) => shell,
+                Some(other) => usage_error(&format!("unsupported shell '{}', expected bash, zsh or fish", other)),
+                None => usage_error("missing shell argument"),
+            };
+            let key = match args.get(2).map(String::as_str) {
+                Some("--bind") => match args.get(3).and_then(|key| shell_key(shell, key)) {
+                    Some(key) => Some(key),
+                    None => usage_error("--bind expects a key like ^p for Ctrl+P or alt-p for Alt+P"),
+                },
+                Some(other) => usage_error(&format!("unexpected argument '{}'", other)),
+                None => None,
+            };
+            print!("{}", shell_init(shell, key.as_deref()));
+        }
+        "rm" => {
+            let force = args[1..].iter().any(|arg| arg == "--force" || arg == "-f");
+            let query = args[1..].iter().find(|arg| !arg.starts_with('-'));
+            let project = resolve(query)?;
+            if dry_run() {
+                println!("Would remove '{}' ({})", project.name, project.directory);
+                for child in read_db()?.iter().filter(|p| p.parent_id.as_deref() == Some(project.id.as_str())) {
+                    println!("Would move '{}' up a level", child.name);
+                }
+                return Ok(());
+            }
+            if !force && !confirm(&format!("Remove '{}' ({})?", project.name, project.directory))? {
+                eprintln!("Aborted");
+                process::exit(EXIT_CANCELLED);
+            }
+            remove_project(&project.id)?;
+            info!("Removed '{}'", project.name);
+        }
+        "-h" | "--help" | "help" => println!("{}", USAGE),
+        other => {
+            eprintln!("error: unknown command '{}'\n\n{}", other, USAGE);
+            process::exit(EXIT_USAGE);
+        }
+    }
+    Ok(())
+}
+
+fn usage_error(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(EXIT_USAGE);
+}
+
+fn resolve(query: Option<&String>) -> Result<Project, Error> {
+    let Some(query) = query else {
+        usage_error("missing project argument");
+    };
+
+    let projects = read_db()?;
+    match find_project(&projects, query) {
+        Some(project) => Ok(project.clone()),
+        None => {
+            eprintln!("error: {}", Error::ProjectNotFound(query.clone()));
+            process::exit(EXIT_FAILURE);
+        }
+    }
+}
+
+/// Like `resolve`, but falls back to the project whose name or alias matches `query`
+/// best, as long as no other project matches as well.
+fn resolve_fuzzy(query: Option<&String>) -> Result<Project, Error> {
+    let Some(query) = query else {
+        usage_error("missing project argument");
+    };
+
+    let projects = read_db()?;
+    if let Some(project) = find_project(&projects, query) {
+        return Ok(project.clone());
+    }
+    let matcher = SkimMatcherV2::default();
+    let mut scored: Vec<(i64, &Project)> = projects
+        .iter()
+        .filter_map(|project| {
+            let score = std::iter::once(&project.name)
+                .chain(project.alias.as_ref())
+                .filter_map(|text| matcher.fuzzy_match(text, query))
+                .max()?;
+            Some((score, project))
+        })
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    match scored.as_slice() {
+        [] => {
+            eprintln!("error: {}", Error::ProjectNotFound(query.clone()));
+            process::exit(EXIT_FAILURE);
+        }
+        [(best, project), rest @ ..] if rest.first().is_none_or(|(score, _)| score < best) => Ok((*project).clone()),
+        tied => {
+            let best = tied[0].0;
+            let names: Vec<&str> = tied
+                .iter()
+                .take_while(|(score, _)| *score == best)
+                .map(|(_, project)| project.name.as_str())
+                .collect();
+            eprintln!("error: '{}' matches several projects: {}", query, names.join(", "));
+            process::exit(EXIT_FAILURE);
+        }
+    }
+}
+
+/// Runs the task `name` of `project` with the shell, passing its output through, and exits
+/// with its exit code.
+fn run_task(project: &Project, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(command) = project.tasks.get(name) else {
+        let names: Vec<&str> = project.tasks.keys().map(String::as_str).collect();
+        if names.is_empty() {
+            eprintln!("error: '{}' has no tasks", project.name);
+        } else {
+            eprintln!("error: '{}' has no task '{}', only {}", project.name, name, names.join(", "));
+        }
+        process::exit(EXIT_FAILURE);
+    };
+
+    let status = script_command(command)
+        .current_dir(&project.directory)
+        .envs(&project.env)
+        .status()?;
+    process::exit(status.code().unwrap_or(1));
+}
+
+fn open_in_editor(project: &Project) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(mut command) = open_command(&config::load()?, project) else {
+        usage_error("the editor command is empty");
+    };
+    let status = command
+        .current_dir(&project.directory)
+        .status()
+        .map_err(|err| format!("can't run {}: {}", command.get_program().to_string_lossy(), err))?;
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+fn add(args: &[String]) -> Result<(), Error> {
+    let mut form = AddForm::default();
+    let mut stdin = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--name" => match args.next() {
+                Some(name) => form.name = name.clone(),
+                None => usage_error("--name expects a value"),
+            },
+            "--stdin" => stdin = true,
+            _ if form.directory.is_empty() => form.directory = arg.clone(),
+            other => usage_error(&format!("unexpected argument '{}'", other)),
+        }
+    }
+    if stdin {
+        if !form.directory.is_empty() || !form.name.is_empty() {
+            usage_error("--stdin takes neither a path nor --name");
+        }
+        return add_from_stdin();
+    }
+    if form.directory.is_empty() {
+        usage_error("missing directory argument");
+    }
+
+    let name = form.submit()?;
+    info!("Added '{}'", name);
+    Ok(())
+}
+
+/// Adds the directories listed on stdin, one per line. A path to a `.git` directory
+/// stands for the repository around it, so that the output of `find -name .git` can be
+/// piped in. Directories added already are skipped.
+fn add_from_stdin() -> Result<(), Error> {
+    let mut directories = Vec::new();
+    for line in io::stdin().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let path = expand_tilde(line);
+        let path = match path.file_name() {
+            Some(name) if name == ".git" => path.parent().map(Path::to_path_buf).unwrap_or(path),
+            _ => path,
+        };
+        match path.canonicalize() {
+            Ok(directory) if directory.is_dir() => directories.push(directory),
+            _ => eprintln!("warning: skipping '{}', not a directory", line),
+        }
+    }
+    directories.sort();
+    directories.dedup();
+    let total = directories.len();
+    let added = add_directories(directories)?;
+    match total - added {
+        0 => info!("Added {}", count_projects(added)),
+        n => info!("Added {}, skipped {} added already", count_projects(added), n),
+    }
+    Ok(())
+}
+
+/// A `whisk` shell function that changes into a project's directory for `whisk cd`, with
+/// the picker or `whisk path`, and passes everything else on to the binary. With `key`,
+/// in the shell's notation, the key does the same as `whisk cd` without a project.
+fn shell_init(shell: &str, key: Option<&str>) -> String {
+    let binary = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.file_name().map(|name| name.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "whisk".to_string());
+    let script = if shell == "fish" {
+        r#"function whisk
+    if test "$argv[1]" = cd
+        set -l dir
+        if test (count $argv) -gt 1
+            set dir (command BINARY path $argv[2..-1]); or return
+        else
+            set dir (command BINARY pick); or return
+        end
+        cd $dir
+    else
+        command BINARY $argv
+    end
+end
+"#
+    } else {
+        r#"whisk() {
+    if [ "$1" = cd ]; then
+        shift
+        local dir
+        if [ $# -gt 0 ]; then
+            dir="$(command BINARY path "$@")" || return
+        else
+            dir="$(command BINARY pick)" || return
+        fi
+        cd -- "$dir"
+    else
+        command BINARY "$@"
+    fi
+}
+"#
+    };
+    let binding = match (shell, key) {
+        (_, None) => "",
+        ("fish", Some(_)) => {
+            r#"
+function __whisk_pick
+    set -l dir (command BINARY pick --sort frecency); and cd $dir
+    commandline -f repaint
+end
+bind KEY __whisk_pick
+"#
+        }
+        ("zsh", Some(_)) => {
+            r#"
+__whisk_pick() {
+    local dir
+    dir="$(command BINARY pick --sort frecency </dev/tty)" && cd -- "$dir"
+    zle reset-prompt
+}
+zle -N __whisk_pick
+bindkey 'KEY' __whisk_pick
+"#
+        }
+        (_, Some(_)) => {
+            r#"
+__whisk_pick() {
+    local dir
+    dir="$(command BINARY pick --sort frecency)" && cd -- "$dir"
+}
+bind -x '"KEY": __whisk_pick'
+"#
+        }
+    };
+    (script.to_string() + binding)
+        .replace("BINARY", &binary)
+        .replace("KEY", key.unwrap_or_default())
+}
+
+/// `key`, like `^p` or `ctrl-p` for Ctrl+P and `alt-p` for Alt+P, in the notation of the
+/// shell's key bindings. `None` if it isn't a letter with Ctrl or Alt.
+fn shell_key(shell: &str, key: &str) -> Option<String> {
+    let key = key.to_lowercase();
+    let (alt, letter) = if let Some(letter) = key.strip_prefix('^').or_else(|| key.strip_prefix("ctrl-")) {
+        (false, letter)
+    } else {
+        (true, key.strip_prefix("alt-")?)
+    };
+    let mut chars = letter.chars();
+    let letter = match (chars.next(), chars.next()) {
+        (Some(letter), None) if letter.is_ascii_lowercase() => letter,
+        _ => return None,
+    };
+    Some(match (shell, alt) {
+        ("fish", false) => format!("\\c{}", letter),
+        ("zsh", false) => format!("^{}", letter),
+        (_, false) => format!("\\C-{}", letter),
+        ("zsh", true) => format!("^[{}", letter),
+        (_, true) => format!("\\e{}", letter),
+    })
+}
+
+/// The directories in zoxide's database, as listed by `zoxide query`.
+fn zoxide_directories() -> Result<Vec<PathBuf>, String> {
+    let output = Command::new("zoxide")
+        .args(["query", "--list"])
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|err| format!("can't run zoxide: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("zoxide failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).lines().map(PathBuf::from).collect())
+}
+
+/// The directories in autojump's database, whose lines are a weight and a path.
+fn autojump_directories() -> Result<Vec<PathBuf>, String> {
+    let path = dirs::data_dir().unwrap_or_default().join("autojump").join("autojump.txt");
+    let content = fs::read_to_string(&path).map_err(|err| format!("can't read {}: {}", path.display(), err))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(_, directory)| PathBuf::from(directory))
+        .collect())
+}
+
+/// The projects JetBrains IDEs opened lately, from the recentProjects.xml of every
+/// version of every IDE. They're the keys of its map, or the values of its list in
+/// older versions, with `$USER_HOME$` for the home directory.
+fn jetbrains_directories() -> Result<Vec<PathBuf>, String> {
+    let root = dirs::config_dir().unwrap_or_default().join("JetBrains");
+    let products = fs::read_dir(&root).map_err(|err| format!("can't read {}: {}", root.display(), err))?;
+    let home = dirs::home_dir().unwrap_or_default().to_string_lossy().to_string();
+    let path = regex::Regex::new(r#"<(?:entry key|option value)="([^"]+)""#).expect("valid pattern");
+    let mut directories = Vec::new();
+    for product in products.flatten() {
+        let file = product.path().join("options").join("recentProjects.xml");
+        let Ok(content) = fs::read_to_string(&file) else {
+            continue;
+        };
+        for captures in path.captures_iter(&content) {
+            let directory = captures[1]
+                .replace("$USER_HOME$", &home)
+                .replace("&quot;", "\"")
+                .replace("&apos;", "'")
+                .replace("&lt;", "<")
+                .replace("&gt;", ">")
+                .replace("&amp;", "&");
+            // Other entries and options have values that aren't paths.
+            if Path::new(&directory).is_absolute() {
+                directories.push(PathBuf::from(directory));
+            }
+        }
+    }
+    Ok(directories)
+}
+
+/// Adds the directories that look like projects and aren't added yet, after listing
+/// them and asking unless `yes` is set.
+fn import(directories: Vec<PathBuf>, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let known: Vec<String> = read_db()?.into_iter().map(|p| p.directory).collect();
+    let mut candidates: Vec<PathBuf> = directories
+        .into_iter()
+        .filter(|directory| directory.is_dir() && looks_like_project(directory))
+        .filter(|directory| !known.contains(&directory.to_string_lossy().to_string()))
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    if candidates.is_empty() {
+        info!("No new projects found");
+        return Ok(());
+    }
+
+    for directory in &candidates {
+        println!("  {}", directory.display());
+    }
+    if dry_run() {
+        println!("Would add {}", count_projects(candidates.len()));
+        return Ok(());
+    }
+    if !yes && !confirm(&format!("Add {}?", count_projects(candidates.len())))? {
+        eprintln!("Aborted");
+        process::exit(EXIT_CANCELLED);
+    }
+    let added = add_directories(candidates)?;
+    info!("Added {}", count_projects(added));
+    Ok(())
+}
+
+/// Clones `url` and adds it as a project.
+fn clone(url: &str, path: Option<&String>, name: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let fail = |message: String| -> ! {
+        eprintln!("error: {}", message);
+        process::exit(EXIT_FAILURE);
+    };
+    let Some(repo_name) = git::repo_name(url) else {
+        usage_error(&format!("can't tell the repository name from '{}'", url));
+    };
+    let target = match path {
+        Some(path) => expand_tilde(path),
+        None => {
+            let config = config::load()?;
+            let Some(root) = clone_root(&config) else {
+                usage_error("missing path argument, and there's no clone_root or project_roots in the config");
+            };
+            root.join(repo_name)
+        }
+    };
+    if target.exists() {
+        fail(format!("'{}' exists already", target.display()));
+    }
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    git::clone(url, &target).unwrap_or_else(|err| fail(err));
+
+    let form = AddForm {
+        directory: target.to_string_lossy().to_string(),
+        name: name.unwrap_or_else(|| repo_name.to_string()),
+        ..AddForm::default()
+    };
+    let name = form.submit()?;
+    info!("Added '{}' in {}", name, target.display());
+    Ok(())
+}
+
+/// Where repositories are cloned without a path: the clone_root of the config, or the
+/// first of its project_roots.
+fn clone_root(config: &config::Config) -> Option<PathBuf> {
+    config
+        .clone_root
+        .as_ref()
+        .or_else(|| config.project_roots.first())
+        .map(|root| expand_tilde(root))
+}
+
+/// Lists the repositories of a user on the forge that aren't projects yet and clones the
+/// chosen ones, or adds them if they're cloned already. The projects keep the URL and
+/// description of their repository.
+fn import_from_forge(name: &str, args: &[String], yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let fail = |message: String| -> ! {
+        eprintln!("error: {}", message);
+        process::exit(EXIT_FAILURE);
+    };
+    let mut user = None;
+    let mut url = None;
+    let mut dir = None;
+    let mut ssh = false;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--yes" | "-y" => {}
+            "--ssh" => ssh = true,
+            "--user" | "--url" | "--dir" => {
+                let Some(value) = rest.next() else {
+                    usage_error(&format!("{} expects a value", arg));
+                };
+                match arg.as_str() {
+                    "--user" => user = Some(value.clone()),
+                    "--url" => url = Some(value.clone()),
+                    _ => dir = Some(expand_tilde(value)),
+                }
+            }
+            other => usage_error(&format!("unexpected argument '{}'", other)),
+        }
+    }
+    let Some(user) = user else {
+        usage_error("missing --user NAME");
+    };
+    let forge = forge::find(name, url.as_deref()).unwrap_or_else(|err| usage_error(&err));
+    let config = config::load()?;
+    let Some(root) = dir.or_else(|| clone_root(&config)) else {
+        usage_error("missing --dir, and there's no clone_root or project_roots in the config");
+    };
+    let token = forge.token(&config);
+
+    let mut projects = read_db()?;
+    let repos: Vec<forge::Repo> = forge
+        .user_repos(&user, token.as_deref())
+        .unwrap_or_else(|err| fail(err))
+        .into_iter()
+        .filter(|repo| {
+            let target = root.join(&repo.name).to_string_lossy().to_string();
+            !projects
+                .iter()
+                .any(|p| p.directory == target || p.repo_url.as_deref().is_some_and(|url| repo.has_url(url)))
+        })
+        .collect();
+    if repos.is_empty() {
+        info!("No new repositories of '{}' on {}", user, forge.name());
+        return Ok(());
+    }
+
+    let mut table = vec![vec!["#".to_string(), "REPOSITORY".to_string(), "DESCRIPTION".to_string()]];
+    for (index, repo) in repos.iter().enumerate() {
+        let mut name = repo.name.clone();
+        for (flag, label) in [(repo.private, " (private)"), (repo.fork, " (fork)"), (repo.archived, " (archived)")] {
+            if flag {
+                name += label;
+            }
+        }
+        let description = repo.description.clone().unwrap_or_default();
+        table.push(vec![(index + 1).to_string(), name, description]);
+    }
+    print_table(&table);
+    let chosen = if yes {
+        (0..repos.len()).collect()
+    } else {
+        eprint!("Clone which? Numbers like 1 3 5-7, or all: ");
+        io::stderr().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        match parse_selection(&answer, repos.len()) {
+            Some(chosen) if !chosen.is_empty() => chosen,
+            Some(_) => {
+                eprintln!("Aborted");
+                process::exit(EXIT_CANCELLED);
+            }
+            None => fail(format!("can't tell which repositories '{}' means", answer.trim())),
+        }
+    };
+
+    let known = projects.len();
+    let (mut added, mut failed) = (0, 0);
+    for repo in chosen.into_iter().map(|index| &repos[index]) {
+        let url = if ssh { &repo.ssh_url } else { &repo.clone_url };
+        let target = root.join(&repo.name);
+        let cloned = target.is_dir();
+        if dry_run() {
+            if cloned {
+                println!("Would add {}", target.display());
+            } else {
+                println!("Would clone {} to {}", url, target.display());
+            }
+            continue;
+        }
+        if !cloned {
+            if target.exists() {
+                eprintln!("error: '{}' exists already and isn't a directory", target.display());
+                failed += 1;
+                continue;
+            }
+            fs::create_dir_all(&root)?;
+            if let Err(err) = git::clone(url, &target) {
+                eprintln!("error: {}", err);
+                failed += 1;
+                continue;
+            }
+        }
+        let directory = target.canonicalize()?.to_string_lossy().to_string();
+        let mut project = new_project(&projects, repo.name.clone(), directory);
+        project.repo_url = Some(url.clone());
+        project.notes = repo.description.clone().unwrap_or_default();
+        projects.push(project);
+        added += 1;
+    }
+    if added > 0 {
+        write_db(&projects)?;
+    }
+    for project in &projects[known..] {
+        hooks::run(hooks::Event::Add, project);
+    }
+    if !dry_run() {
+        info!("Added {}", count_projects(added));
+    }
+    if failed > 0 {
+        process::exit(EXIT_FAILURE);
+    }
+    Ok(())
+}
+
+/// The indexes chosen by an answer like `1 3 5-7` or `all`, out of `count` numbered from 1.
+/// `None` if a number is out of range or not a number.
+fn parse_selection(answer: &str, count: usize) -> Option<Vec<usize>> {
+    if answer.trim().eq_ignore_ascii_case("all") {
+        return Some((0..count).collect());
+    }
+    let mut chosen = Vec::new();
+    for part in answer.split(|c: char| c == ',' || c.is_whitespace()).filter(|part| !part.is_empty()) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.parse::<usize>().ok()?, last.parse::<usize>().ok()?),
+            None => {
+                let number = part.parse::<usize>().ok()?;
+                (number, number)
+            }
+        };
+        if first == 0 || last > count || first > last {
+            return None;
+        }
+        chosen.extend(first - 1..last);
+    }
+    chosen.sort_unstable();
+    chosen.dedup();
+    Some(chosen)
+}
+
+fn rename(project: &Project, name: &str) -> Result<(), Error> {
+    if name.is_empty() {
+        return Err(Error::EmptyName);
+    }
+    if read_db()?.iter().any(|p| p.id != project.id && p.name == name) {
+        return Err(Error::DuplicateName(name.to_string()));
+    }
+    update_projects(std::slice::from_ref(&project.id), |p| p.name = name.to_string())
+}
+
+/// Changes the directory of `project` to `target`, moving the directory first with
+/// `relocate`. Sub-projects inside the old directory move along.
+fn move_project(project: &Project, target: &str, relocate: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let fail = |message: String| -> ! {
+        eprintln!("error: {}", message);
+        process::exit(EXIT_FAILURE);
+    };
+    let target = expand_tilde(target);
+    if relocate {
+        if !Path::new(&project.directory).is_dir() {
+            fail(format!("'{}' doesn't exist, there's nothing to move", project.directory));
+        }
+        if target.exists() {
+            fail(format!("'{}' exists already", target.display()));
+        }
+        fs::rename(&project.directory, &target)
+            .unwrap_or_else(|err| fail(format!("can't move '{}': {}", project.directory, err)));
+    } else if !target.is_dir() {
+        fail(Error::NotADirectory(target.display().to_string()).to_string());
+    }
+    let target = target.canonicalize()?;
+
+    let old = PathBuf::from(&project.directory);
+    let mut projects = read_db()?;
+    for p in projects.iter_mut() {
+        if let Ok(rest) = Path::new(&p.directory).strip_prefix(&old) {
+            p.directory = target.join(rest).to_string_lossy().trim_end_matches('/').to_string();
+        }
+    }
+    write_db(&projects)?;
+    info!("Moved '{}' to {}", project.name, target.display());
+    Ok(())
+}
+
+/// Runs a command in the directories of several projects, one after another.
+fn exec(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(separator) = args.iter().position(|arg| arg == "--") else {
+        usage_error("exec expects the command after --");
+    };
+    let (options, command) = (&args[..separator], &args[separator + 1..]);
+    if command.is_empty() {
+        usage_error("missing command after --");
+    }
+    let mut all = false;
+    let mut jobs = 1;
+    let mut filters = Filters::default();
+    let mut options = options.iter();
+    while let Some(arg) = options.next() {
+        if arg == "--all" {
+            all = true;
+            continue;
+        }
+        let Some(value) = options.next() else {
+            usage_error(&format!("unexpected argument '{}'", arg));
+        };
+        if arg == "--jobs" || arg == "-j" {
+            match value.parse() {
+                Ok(n) if n > 0 => jobs = n,
+                _ => usage_error(&format!("--jobs expects a positive number, not '{}'", value)),
+            }
+        } else if !filters.parse(arg, value) {
+            usage_error(&format!("unexpected argument '{}'", arg));
+        }
+    }
+    if !all && filters.is_empty() {
+        usage_error("exec needs --all or a filter to know which projects to run in");
+    }
+
+    let projects: Vec<Project> = read_db()?.into_iter().filter(|p| filters.matches(p)).collect();
+    let started = Instant::now();
+    let mut failed = Vec::new();
+    if jobs == 1 {
+        for project in &projects {
+            info!("==> {} ({})", project.name, project.directory);
+            let run = exec_in(project, command, false);
+            if let Some(err) = run.error {
+                eprintln!("error: {}", err);
+                failed.push(project.name.as_str());
+            }
+            info!();
+        }
+    } else {
+        let progress = io::stderr().is_terminal();
+        if progress {
+            draw_progress(0, projects.len());
+        }
+        let mut finished = 0;
+        for_each_parallel(&projects, jobs, |project| exec_in(project, command, true), |index, run| {
+            let project = &projects[index];
+            if progress {
+                eprint!("\r\x1b[2K");
+            }
+            info!("==> {} ({})", project.name, project.directory);
+            let _ = io::stdout().write_all(&run.stdout);
+            let _ = io::stdout().flush();
+            let _ = io::stderr().write_all(&run.stderr);
+            if let Some(err) = run.error {
+                eprintln!("error: {}", err);
+                failed.push(project.name.as_str());
+            }
+            info!();
+            finished += 1;
+            if progress {
+                draw_progress(finished, projects.len());
+            }
+            true
+        });
+        if progress {
+            eprint!("\r\x1b[2K");
+        }
+    }
+
+    let summary = format!("whisk exec {}", command.join(" "));
+    if failed.is_empty() {
+        info!("Succeeded in {}", count_projects(projects.len()));
+        notify::task_done(started.elapsed(), &summary, &format!("Succeeded in {}", count_projects(projects.len())));
+        Ok(())
+    } else {
+        println!("Failed in {} of {}: {}", failed.len(), projects.len(), failed.join(", "));
+        notify::task_done(started.elapsed(), &summary, &format!("Failed in {} of {}", failed.len(), projects.len()));
+        process::exit(EXIT_FAILURE);
+    }
+}
+
+fn stats(json: bool) -> Result<(), Error> {
+    let projects = read_db()?;
+    let statuses: Vec<(Status, usize)> = Status::ALL
+        .iter()
+        .map(|&status| (status, projects.iter().filter(|p| p.status == status).count()))
+        .filter(|(_, count)| *count > 0)
+        .collect();
+    let tags = tag_counts(&projects);
+    let oldest = projects.iter().min_by_key(|p| p.created_at);
+    let newest = projects.iter().max_by_key(|p| p.created_at);
+    let most_opened = projects.iter().filter(|p| p.open_count > 0).max_by_key(|p| p.open_count);
+    let tracked = projects.iter().fold(chrono::Duration::zero(), |total, p| total + p.total_time());
+
+    if json {
+        let project = |p: &Project| json!({ "id": p.id, "name": p.name, "created": p.created_at, "opened": p.open_count });
+        let statuses: serde_json::Map<String, Value> = statuses
+            .iter()
+            .map(|(status, count)| (status.label().to_lowercase().replace(' ', "-"), json!(count)))
+            .collect();
+        print_json(&json!({
+            "projects": projects.len(),
+            "statuses": statuses,
+            "tags": tag_counts_json(&tags),
+            "oldest": oldest.map(project),
+            "newest": newest.map(project),
+            "most_opened": most_opened.map(project),
+            "tracked_seconds": tracked.num_seconds(),
+        }));
+        return Ok(());
+    }
+    let breakdown: Vec<String> = statuses
+        .iter()
+        .map(|(status, count)| format!("{} {}", count, status.label().to_lowercase()))
+        .collect();
+    let created = |p: &Project| format!("{} ({})", p.name, p.created_at.with_timezone(&Local).format("%Y-%m-%d"));
+    let table = vec![
+        vec!["Projects".to_string(), format!("{} ({})", projects.len(), breakdown.join(", "))],
+        vec!["Oldest".to_string(), oldest.map(created).unwrap_or_default()],
+        vec!["Newest".to_string(), newest.map(created).unwrap_or_default()],
+        vec![
+            "Most opened".to_string(),
+            most_opened
+                .map(|p| format!("{} ({} time{})", p.name, p.open_count, if p.open_count == 1 { "" } else { "s" }))
+                .unwrap_or_default(),
+        ],
+        vec!["Tracked time".to_string(), format_duration(tracked)],
+    ];
+    print_table(&table);
+    if !tags.is_empty() {
+        let mut table = vec![vec!["TAG".to_string(), "PROJECTS".to_string()]];
+        table.extend(tags.iter().map(|(tag, count)| vec![tag.to_string(), count.to_string()]));
+        println!();
+        print_table(&table);
+    }
+    Ok(())
+}
+
+/// The counts of `tag_counts` as a JSON object from tag to count.
+fn tag_counts_json(tags: &[(String, usize)]) -> Value {
+    Value::Object(tags.iter().map(|(tag, count)| (tag.clone(), json!(count))).collect())
+}
+
+fn tag(args: &[String], json: bool) -> Result<(), Error> {
+    let subcommand = args.first().map(String::as_str);
+    if json && subcommand != Some("list") {
+        usage_error("only tag list has JSON output");
+    }
+    if dry_run() && !matches!(subcommand, Some("add" | "remove")) {
+        usage_error("only tag add and tag remove have a dry run");
+    }
+    match subcommand {
+        Some(action @ ("add" | "remove")) => {
+            let project = resolve(args.get(1))?;
+            let tags: Vec<&str> = args[2..]
+                .iter()
+                .map(|tag| tag.trim().trim_start_matches('#'))
+                .filter(|tag| !tag.is_empty())
+                .collect();
+            if tags.is_empty() {
+                usage_error("missing tag argument");
+            }
+            let change = |current: &mut Vec<String>| {
+                for tag in &tags {
+                    if action == "remove" {
+                        current.retain(|t| t != tag);
+                    } else if !current.iter().any(|t| t == tag) {
+                        current.push(tag.to_string());
+                    }
+                }
+            };
+            if dry_run() {
+                let mut result = project.tags.clone();
+                change(&mut result);
+                match result.len() {
+                    0 => println!("Would leave '{}' without tags", project.name),
+                    _ => println!("Would change the tags of '{}' to {}", project.name, result.join(", ")),
+                }
+                return Ok(());
+            }
+            let mut result = Vec::new();
+            update_projects(std::slice::from_ref(&project.id), |p| {
+                change(&mut p.tags);
+                result = p.tags.clone();
+            })?;
+            match result.len() {
+                0 => info!("'{}' has no tags", project.name),
+                _ => info!("Tags of '{}': {}", project.name, result.join(", ")),
+            }
+        }
+        Some("list") => match args.get(1) {
+            Some(_) => {
+                let project = resolve(args.get(1))?;
+                if json {
+                    print_json(&json!(project.tags));
+                } else {
+                    for tag in &project.tags {
+                        println!("{}", tag);
+                    }
+                }
+            }
+            None => {
+                let projects = read_db()?;
+                let tags = tag_counts(&projects);
+                if json {
+                    print_json(&tag_counts_json(&tags));
+                } else if !tags.is_empty() {
+                    let mut table = vec![vec!["TAG".to_string(), "PROJECTS".to_string()]];
+                    table.extend(tags.iter().map(|(tag, count)| vec![tag.to_string(), count.to_string()]));
+                    print_table(&table);
+                }
+            }
+        },
+        Some(other) => usage_error(&format!("unknown tag command '{}', expected add, remove or list", other)),
+        None => usage_error("missing tag command"),
+    }
+    Ok(())
+}
+
+/// Writes a tmuxinator or tmuxp config for every project, or those matching the filters.
+fn export(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let format = match args.first() {
+        Some(name) => match export::Format::parse(name) {
+            Some(format) => format,
+            None => usage_error(&format!("can't export to '{}', only tmuxinator or tmuxp", name)),
+        },
+        None => usage_error("missing format argument"),
+    };
+    let mut dir = format.default_dir();
+    let mut force = false;
+    let mut filters = Filters::default();
+    let mut rest = args[1..].iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--force" | "-f" => force = true,
+            option if option.starts_with('-') => {
+                let Some(value) = rest.next() else {
+                    usage_error(&format!("{} expects a value", option));
+                };
+                if option == "--dir" {
+                    dir = expand_tilde(value);
+                } else if !filters.parse(option, value) {
+                    usage_error(&format!("unexpected argument '{}'", option));
+                }
+            }
+            other => usage_error(&format!("unexpected argument '{}'", other)),
+        }
+    }
+
+    let windows = config::load()?.tmux_windows;
+    let projects: Vec<Project> = read_db()?
+        .into_iter()
+        .filter(|p| filters.matches(p) && !p.is_missing())
+        .collect();
+    let (mut written, mut skipped) = (0, 0);
+    for project in &projects {
+        let path = dir.join(format.file_name(project));
+        if path.exists() && !force {
+            skipped += 1;
+            continue;
+        }
+        if dry_run() {
+            println!("Would write {}", path.display());
+        } else {
+            fs::create_dir_all(&dir)?;
+            fs::write(&path, format.render(project, &windows))?;
+        }
+        written += 1;
+    }
+    if dry_run() {
+        return Ok(());
+    }
+    let configs = if written == 1 { "config" } else { "configs" };
+    match skipped {
+        0 => info!("Wrote {} {} to {}", written, configs, dir.display()),
+        n => info!(
+            "Wrote {} {} to {}, skipped {} that exist already (--force overwrites them)",
+            written,
+            configs,
+            dir.display(),
+            n
+        ),
+    }
+    Ok(())
+}
+
+/// Prints the lines matching a pattern in the projects matching the filters in `args`,
+/// grouped by project. Exits with 1 when nothing matches, like grep.
+fn grep(args: &[String]) -> Result<(), Error> {
+    let mut pattern = None;
+    let mut ignore_case = false;
+    let mut globs = Vec::new();
+    let mut filters = Filters::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--ignore-case" => ignore_case = true,
+            option if option.starts_with('-') => {
+                let Some(value) = args.next() else {
+                    usage_error(&format!("{} expects a value", option));
+                };
+                if option == "--glob" {
+                    globs.push(value.clone());
+                } else if !filters.parse(option, value) {
+                    usage_error(&format!("unexpected argument '{}'", option));
+                }
+            }
+            _ if pattern.is_none() => pattern = Some(arg.clone()),
+            other => usage_error(&format!("unexpected argument '{}'", other)),
+        }
+    }
+    let Some(pattern) = pattern else {
+        usage_error("missing pattern argument");
+    };
+    let pattern = match regex::RegexBuilder::new(&pattern).case_insensitive(ignore_case).build() {
+        Ok(pattern) => pattern,
+        Err(err) => usage_error(&format!("invalid pattern: {}", err)),
+    };
+
+    let projects: Vec<Project> = read_db()?
+        .into_iter()
+        .filter(|p| filters.matches(p) && !p.is_missing())
+        .collect();
+    let mut found = false;
+    let jobs = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut stdout = io::stdout().lock();
+    let mut searched: Vec<Option<Vec<_>>> = projects.iter().map(|_| None).collect();
+    let mut next = 0;
+    let search = |project: &Project| search::search(Path::new(&project.directory), &pattern, &globs);
+    for_each_parallel(&projects, jobs, search, |index, matches| {
+        searched[index] = Some(matches);
+        // Printed in the order of the projects, so one searched early waits for those before it.
+        while let Some(matches) = searched.get_mut(next).and_then(Option::take) {
+            let project = &projects[next];
+            next += 1;
+            if matches.is_empty() {
+                continue;
+            }
+            let mut lines = vec![format!("==> {} ({})", project.name, project.directory)];
+            for found in &matches {
+                lines.push(format!("{}:{}: {}", found.path.display(), found.line_number, found.line));
+            }
+            lines.push(String::new());
+            found = true;
+            // Stop quietly when the output is piped into something like head.
+            if writeln!(stdout, "{}", lines.join("\n")).is_err() {
+                return false;
+            }
+        }
+        true
+    });
+    if !found {
+        process::exit(EXIT_FAILURE);
+    }
+    Ok(())
+}
+
+/// Prints the git state of the repositories among the projects matching the filters in
+/// `args`.
+fn git_status(args: &[String], json: bool) -> Result<(), Error> {
+    let mut filters = Filters::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else {
+            usage_error(&format!("unexpected argument '{}'", arg));
+        };
+        if !filters.parse(arg, value) {
+            usage_error(&format!("unexpected argument '{}'", arg));
+        }
+    }
+
+    let projects: Vec<Project> = read_db()?.into_iter().filter(|p| filters.matches(p)).collect();
+    // Each lookup runs git twice, so they run side by side.
+    let statuses: Vec<Option<GitStatus>> = thread::scope(|scope| {
+        let lookups: Vec<_> = projects
+            .iter()
+            .map(|project| scope.spawn(|| git::status(&project.directory)))
+            .collect();
+        lookups.into_iter().map(|lookup| lookup.join().unwrap_or(None)).collect()
+    });
+    let mut repositories: Vec<(Project, GitStatus)> = projects
+        .into_iter()
+        .zip(statuses)
+        .filter_map(|(project, status)| Some((project, status?)))
+        .collect();
+    repositories.sort_by_key(|(project, status)| (!status.needs_attention(), project.name.to_lowercase()));
+
+    if json {
+        let objects: Vec<Value> = repositories
+            .iter()
+            .map(|(project, status)| {
+                json!({
+                    "id": project.id,
+                    "name": project.name,
+                    "directory": project.directory,
+                    "branch": status.branch,
+                    "changed": status.changed,
+                    "ahead": status.ahead,
+                    "behind": status.behind,
+                    "last_commit": status.last_commit.as_ref().map(|commit| json!({
+                        "hash": commit.hash,
+                        "author": commit.author,
+                        "subject": commit.subject,
+                        "committed_at": commit.committed_at,
+                    })),
+                })
+            })
+            .collect();
+        print_json(&Value::Array(objects));
+        return Ok(());
+    }
+    let mut table = vec![["NAME", "BRANCH", "CHANGED", "UNPUSHED", "UNPULLED", "LAST COMMIT"].map(String::from).to_vec()];
+    for (project, status) in &repositories {
+        let count = |n: u32| if n == 0 { String::new() } else { n.to_string() };
+        table.push(vec![
+            project.name.clone(),
+            status.branch.clone(),
+            count(status.changed),
+            count(status.ahead),
+            count(status.behind),
+            status.last_commit.as_ref().map(|commit| relative_time(commit.committed_at)).unwrap_or_default(),
+        ]);
+    }
+    print_table(&table);
+    let attention = repositories.iter().filter(|(_, status)| status.needs_attention()).count();
+    match attention {
+        0 => info!("\nAll {} repositories are clean and in sync", repositories.len()),
+        n => info!("\n{} of {} repositories need attention", n, repositories.len()),
+    }
+    Ok(())
+}
+
+/// Prints the backups, newest first and numbered for `restore`.
+fn list_backups() -> Result<(), Error> {
+    let backups = backup::list()?;
+    if backups.is_empty() {
+        info!("No backups yet, `whisk backup` makes one");
+        return Ok(());
+    }
+    let mut table = vec![["#", "CREATED", "PROJECTS", "FILE"].map(String::from).to_vec()];
+    for (i, backup) in backups.iter().enumerate() {
+        table.push(vec![
+            (i + 1).to_string(),
+            format!(
+                "{} ({})",
+                backup.created_at.format("%Y-%m-%d %H:%M:%S"),
+                relative_time(backup.created_at.with_timezone(&Utc))
+            ),
+            backup.read().map_or("unreadable".to_string(), |projects| projects.len().to_string()),
+            backup.file_name(),
+        ]);
+    }
+    print_table(&table);
+    Ok(())
+}
+
+/// Restores the backup numbered `choice` in `list_backups`, or with that file name.
+fn restore(choice: &str, yes: bool) -> Result<(), Error> {
+    let mut backups = backup::list()?;
+    let index = match choice.parse::<usize>() {
+        Ok(n) => n.checked_sub(1).filter(|&i| i < backups.len()),
+        Err(_) => backups.iter().position(|backup| backup.file_name() == choice),
+    };
+    let Some(index) = index else {
+        eprintln!("error: {}", Error::BackupNotFound(choice.to_string()));
+        process::exit(EXIT_FAILURE);
+    };
+    let backup = backups.swap_remove(index);
+    let projects = match backup.read() {
+        Ok(projects) => projects.len(),
+        Err(err) => {
+            eprintln!("error: can't restore {}: {}", backup.file_name(), err);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+    let replacement = format!(
+        "the current {} with the {} from {}",
+        count_projects(read_db()?.len()),
+        count_projects(projects),
+        backup.created_at.format("%Y-%m-%d %H:%M:%S"),
+    );
+    if dry_run() {
+        println!("Would replace {}", replacement);
+        return Ok(());
+    }
+    let question = format!("Replace {}?", replacement);
+    if !yes && !confirm(&question)? {
+        eprintln!("Aborted");
+        process::exit(EXIT_CANCELLED);
+    }
+    let safety_copy = backup::restore(&backup)?;
+    info!("Restored {}; the previous projects were saved to {}", count_projects(projects), safety_copy.file_name());
+    Ok(())
+}
+
+/// What running a command in one project gave.
+struct ExecRun {
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    /// Why it failed, if it did.
+    error: Option<String>,
+}
+
+/// Runs `work` for every project, `jobs` at a time, and hands each result to `done` with
+/// the index of its project as soon as it's there, so in the order they finish. Stops
+/// starting new work once `done` returns false. A panic in `work` isn't swallowed, it's
+/// raised again once the running work is done.
+fn for_each_parallel<'a, R: Send>(
+    projects: &'a [Project],
+    jobs: usize,
+    work: impl Fn(&'a Project) -> R + Sync,
+    mut done: impl FnMut(usize, R) -> bool,
+) {
+    let next = AtomicUsize::new(0);
+    let (finished, results) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.min(projects.len()) {
+            let finished = finished.clone();
+            let (next, work) = (&next, &work);
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(project) = projects.get(index) else {
+                    break;
+                };
+                if finished.send((index, work(project))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(finished);
+
+        for (index, result) in results {
+            if !done(index, result) {
+                break;
+            }
+        }
+    });
+}
+
+/// Runs `command` in the directory of `project`. With `capture`, its output is kept in
+/// the result instead of going straight to the terminal, so that projects running at
+/// the same time don't interleave theirs.
+fn exec_in(project: &Project, command: &[String], capture: bool) -> ExecRun {
+    let mut run = ExecRun { stdout: Vec::new(), stderr: Vec::new(), error: None };
+    if project.is_missing() {
+        run.error = Some("the directory doesn't exist".to_string());
+        return run;
+    }
+    let mut cmd = Command::new(&command[0]);
+    cmd.args(&command[1..]).current_dir(&project.directory).envs(&project.env);
+    let status = if capture {
+        cmd.stdin(Stdio::null()).output().map(|output| {
+            run.stdout = output.stdout;
+            run.stderr = output.stderr;
+            output.status
+        })
+    } else {
+        cmd.status()
+    };
+    run.error = match status {
+        Ok(status) if status.success() => None,
+        Ok(status) => Some(format!("exited with {}", status.code().map_or("a signal".to_string(), |c| c.to_string()))),
+        Err(err) => Some(format!("can't run {}: {}", command[0], err)),
+    };
+    run
+}
+
+/// Like "[#########           ] 3/7", on stderr and without a newline so that it can be
+/// redrawn in place.
+fn draw_progress(finished: usize, total: usize) {
+    const WIDTH: usize = 20;
+    let filled = WIDTH * finished / total.max(1);
+    eprint!("\r[{}{}] {}/{}", "#".repeat(filled), " ".repeat(WIDTH - filled), finished, total);
+    let _ = io::stderr().flush();
+}
+
+/// Removes or archives the projects whose directories don't exist anymore.
+fn prune(archive: bool, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let missing: Vec<Project> = read_db()?.into_iter().filter(Project::is_missing).collect();
+    if missing.is_empty() {
+        info!("All project directories exist");
+        return Ok(());
+    }
+
+    let rows: Vec<Vec<String>> = missing.iter().map(|p| vec![format!("  {}", p.name), p.directory.clone()]).collect();
+    print_table(&rows);
+    let action = if archive { "Archive" } else { "Remove" };
+    if dry_run() {
+        println!("Would {} {}", action.to_lowercase(), count_projects(missing.len()));
+        return Ok(());
+    }
+    if !yes && !confirm(&format!("{} {}?", action, count_projects(missing.len())))? {
+        eprintln!("Aborted");
+        process::exit(EXIT_CANCELLED);
+    }
+    if archive {
+        let ids: Vec<String> = missing.iter().map(|p| p.id.clone()).collect();
+        update_projects(&ids, |p| p.status = Status::Archived)?;
+        info!("Archived {}", count_projects(missing.len()));
+    } else {
+        for project in &missing {
+            remove_project(&project.id)?;
+        }
+        info!("Removed {}", count_projects(missing.len()));
+    }
+    Ok(())
+}
+
+/// The directories of `roots`, or of the project_roots of the config without any,
+/// leaving out those that don't exist.
+fn root_directories(mut roots: Vec<String>) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    if roots.is_empty() {
+        roots = config::load()?.project_roots;
+    }
+    if roots.is_empty() {
+        usage_error("missing root argument, and there are no project_roots in the config");
+    }
+    let mut directories = Vec::new();
+    for root in &roots {
+        match expand_tilde(root).canonicalize() {
+            Ok(directory) if directory.is_dir() => directories.push(directory),
+            _ => eprintln!("warning: skipping '{}', not a directory", root),
+        }
+    }
+    Ok(directories)
+}
+
+/// Looks through `roots` every `interval` for good. Projects that show up are added, and
+/// projects below the roots whose directory goes away are reported. What's there when it
+/// starts is left to `discover` and `prune`, it only follows the changes.
+fn watch(roots: &[PathBuf], depth: usize, interval: Duration) -> Result<(), Box<dyn std::error::Error>> {
+    let below_roots = |project: &Project| roots.iter().any(|root| Path::new(&project.directory).starts_with(root));
+    let missing_ids = |projects: &[Project]| -> Vec<String> {
+        projects.iter().filter(|p| below_roots(p) && p.is_missing()).map(|p| p.id.clone()).collect()
+    };
+    let roots_list: Vec<String> = roots.iter().map(|root| root.display().to_string()).collect();
+    info!("Watching {}, stop with Ctrl-C", roots_list.join(", "));
+
+    let notifications = notify::enabled();
+    let mut seen = discover::discover(roots, depth, || false);
+    let mut missing = missing_ids(&read_db()?);
+    loop {
+        thread::sleep(interval);
+        let found = discover::discover(roots, depth, || false);
+        let appeared: Vec<PathBuf> = found.iter().filter(|directory| !seen.contains(directory)).cloned().collect();
+        seen = found;
+        // An error is only about this look, the next one may go better.
+        let projects = match read_db() {
+            Ok(projects) => projects,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                continue;
+            }
+        };
+
+        let appeared: Vec<PathBuf> = appeared
+            .into_iter()
+            .filter(|directory| !projects.iter().any(|p| Path::new(&p.directory) == directory))
+            .collect();
+        if !appeared.is_empty() {
+            match add_directories(appeared.clone()) {
+                Ok(_) => {
+                    for directory in &appeared {
+                        info!("Added {}", directory.display());
+                    }
+                    if notifications {
+                        let names: Vec<String> = appeared
+                            .iter()
+                            .map(|directory| directory.file_name().unwrap_or_default().to_string_lossy().to_string())
+                            .collect();
+                        notify::send(&format!("Added {}", count_projects(names.len())), &names.join(", "));
+                    }
+                }
+                Err(err) => eprintln!("error: {}", err),
+            }
+        }
+
+        let now_missing = missing_ids(&projects);
+        for project in projects.iter().filter(|p| now_missing.contains(&p.id) && !missing.contains(&p.id)) {
+            eprintln!("warning: the directory of '{}' was removed: {}", project.name, project.directory);
+            if notifications {
+                notify::send(&format!("'{}' is missing", project.name), &format!("{} was removed", project.directory));
+            }
+        }
+        missing = now_missing;
+    }
+}
+
+/// Asks `question` on the terminal, true if it's answered with yes.
+fn confirm(question: &str) -> io::Result<bool> {
+    eprint!("{} [y/N] ", question);
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Finds the option whose label, lowercase with dashes for spaces, is `name`.
+fn parse_choice<T: Copy>(options: &[T], label: impl Fn(T) -> &'static str, name: &str) -> Option<T> {
+    options
+        .iter()
+        .copied()
+        .find(|option| label(*option).to_lowercase().replace(' ', "-") == name.to_lowercase())
+}
+
+/// The `--status`, `--tag` and `--workspace` options that narrow down the projects a
+/// command works on.
+#[derive(Default)]
+struct Filters {
+    status: Option<Status>,
+    tag: Option<String>,
+    workspace: Option<String>,
+}
+
+impl Filters {
+    /// Takes the option `arg` with `value` if it's a filter, false otherwise.
+    fn parse(&mut self, arg: &str, value: &str) -> bool {
+        match arg {
+            "--status" => match parse_choice(&Status::ALL, Status::label, value) {
+                Some(status) => self.status = Some(status),
+                None => usage_error(&format!("unknown status '{}'", value)),
+            },
+            "--tag" => self.tag = Some(value.to_string()),
+            "--workspace" => self.workspace = Some(value.to_string()),
+            _ => return false,
+        }
+        true
+    }
+
+    fn is_empty(&self) -> bool {
+        self.status.is_none() && self.tag.is_none() && self.workspace.is_none()
+    }
+
+    fn matches(&self, project: &Project) -> bool {
+        self.status.is_none_or(|status| project.status == status)
+            && self.tag.as_ref().is_none_or(|tag| project.tags.contains(tag))
+            && (self.workspace.is_none() || project.workspace == self.workspace)
+    }
+}
+
+/// Prints the projects as a table, with the columns, order and filters from the options.
+fn list(args: &[String], json: bool) -> Result<(), Error> {
+    let mut columns = if json { COLUMNS.join(",") } else { DEFAULT_COLUMNS.to_string() };
+    let mut sort_mode = SortMode::Added;
+    let mut filters = Filters::default();
+    let mut dmenu = false;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else {
+            usage_error(&format!("{} expects a value", arg));
+        };
+        match arg.as_str() {
+            "--columns" => columns = value.clone(),
+            "--format" => match value.as_str() {
+                "dmenu" => dmenu = true,
+                "table" => dmenu = false,
+                other => usage_error(&format!("unknown format '{}', expected table or dmenu", other)),
+            },
+            "--sort" => match parse_choice(&SortMode::ALL, SortMode::label, value) {
+                Some(mode) => sort_mode = mode,
+                None => usage_error(&format!("unknown sort mode '{}'", value)),
+            },
+            _ if filters.parse(arg, value) => {}
+            other => usage_error(&format!("unexpected argument '{}'", other)),
+        }
+    }
+    let columns: Vec<&str> = columns.split(',').map(str::trim).collect();
+    if let Some(unknown) = columns.iter().find(|column| !COLUMNS.contains(column)) {
+        usage_error(&format!("unknown column '{}'", unknown));
+    }
+
+    if json && dmenu {
+        usage_error("--json and --format dmenu can't be combined");
+    }
+
+    let mut projects: Vec<Project> = read_db()?.into_iter().filter(|p| filters.matches(p)).collect();
+    sort_projects(&mut projects, sort_mode);
+
+    if dmenu {
+        let mut stdout = io::stdout().lock();
+        for project in &projects {
+            if writeln!(stdout, "{}\t{}", project.name.replace('\t', " "), project.directory).is_err() {
+                break;
+            }
+        }
+        return Ok(());
+    }
+    if json {
+        let objects: Vec<Value> = projects
+            .iter()
+            .map(|project| columns.iter().map(|column| (column.to_string(), column_json(project, column))).collect())
+            .collect();
+        print_json(&Value::Array(objects));
+        return Ok(());
+    }
+    let mut table = vec![columns.iter().map(|column| column.to_uppercase()).collect::<Vec<_>>()];
+    for project in &projects {
+        table.push(columns.iter().map(|column| column_value(project, column)).collect());
+    }
+    print_table(&table);
+    Ok(())
+}
+
+/// The value of `--field` for pick and resolve.
+fn parse_field(value: Option<&String>) -> &'static str {
+    match value.map(String::as_str) {
+        Some("path") => "path",
+        Some("name") => "name",
+        Some("id") => "id",
+        Some("alias") => "alias",
+        Some(other) => usage_error(&format!("unknown field '{}', expected path, name, id or alias", other)),
+        None => usage_error("--field expects path, name, id or alias"),
+    }
+}
+
+fn field_value(project: &Project, field: &str) -> String {
+    match field {
+        "name" => project.name.clone(),
+        "id" => project.id.clone(),
+        "alias" => project.alias.clone().unwrap_or_default(),
+        _ => project.directory.clone(),
+    }
+}
+
+/// The project on a line printed by `list --format dmenu`, found by its directory, or
+/// the one `selection` references when it's just a name, alias or ID.
+fn resolve_selection(selection: &str) -> Result<Project, Error> {
+    let selection = selection.trim_end_matches(['\r', '\n']);
+    let Some((_, directory)) = selection.split_once('\t') else {
+        return resolve(Some(&selection.to_string()));
+    };
+    match read_db()?.into_iter().find(|p| p.directory == directory) {
+        Some(project) => Ok(project),
+        None => {
+            eprintln!("error: {}", Error::ProjectNotFound(selection.to_string()));
+            process::exit(EXIT_FAILURE);
+        }
+    }
+}
+
+fn column_value(project: &Project, column: &str) -> String {
+    match column {
+        "id" => project.short_id().to_string(),
+        "name" => project.name.clone(),
+        "alias" => project.alias.clone().unwrap_or_default(),
+        "status" => project.status.label().to_string(),
+        "priority" => project.priority.label().to_string(),
+        "directory" => project.directory.clone(),
+        "tags" => project.tags.join(","),
+        "workspace" => project.workspace.clone().unwrap_or_default(),
+        "due" => project.due_date.map(|due| due.to_string()).unwrap_or_default(),
+        "created" => project.created_at.with_timezone(&Local).format("%Y-%m-%d").to_string(),
+        "opened" => project
+            .last_opened_at
+            .map(|at| at.with_timezone(&Local).format("%Y-%m-%d").to_string())
+            .unwrap_or_default(),
+        "size" => project.disk_usage.map(|usage| format_size(usage.bytes)).unwrap_or_default(),
+        _ => String::new(),
+    }
+}
+
+/// Like `column_value`, but with typed values and full IDs and timestamps. Missing values
+/// are `null`.
+fn column_json(project: &Project, column: &str) -> Value {
+    match column {
+        "id" => json!(project.id),
+        "tags" => json!(project.tags),
+        "due" => json!(project.due_date),
+        "created" => json!(project.created_at),
+        "opened" => json!(project.last_opened_at),
+        "size" => json!(project.disk_usage.map(|usage| usage.bytes)),
+        "alias" => json!(project.alias),
+        "workspace" => json!(project.workspace),
+        _ => json!(column_value(project, column)),
+    }
+}
+
+/// Prints `value` pretty-printed. Like `print_table`, a closed pipe just ends the output.
+fn print_json(value: &Value) {
+    let text = serde_json::to_string_pretty(value).expect("JSON values serialize");
+    let _ = writeln!(io::stdout(), "{}", text);
+}
+
+/// Prints `rows` with every column as wide as its widest cell. Stops quietly when the
+/// reader goes away, like `head` does.
+fn print_table(rows: &[Vec<String>]) {
+    let mut stdout = io::stdout().lock();
+    let columns = rows.first().map_or(0, Vec::len);
+    let widths: Vec<usize> = (0..columns)
+        .map(|i| rows.iter().map(|row| row[i].chars().count()).max().unwrap_or(0))
+        .collect();
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        if writeln!(stdout, "{}", cells.join("  ").trim_end()).is_err() {
+            return;
+        }
+    }
+}
+
+/// Prints open projects with a due date, soonest first.
+fn list_due(within: Option<i64>, json: bool) -> Result<(), Error> {
+    let today = Local::now().date_naive();
+    let mut projects: Vec<Project> = read_db()?
+        .into_iter()
+        .filter(|p| matches!(p.status, Status::Active | Status::OnHold))
+        .filter(|p| {
+            p.due_date.is_some_and(|due| {
+                within.is_none_or(|days| (due - today).num_days() <= days)
+            })
+        })
+        .collect();
+    projects.sort_by_key(|p| p.due_date);
+
+    if json {
+        let objects: Vec<Value> = projects
+            .iter()
+            .map(|project| {
+                let due = project.due_date.expect("only projects with a due date are listed");
+                json!({ "id": project.id, "name": project.name, "due": due, "days_left": (due - today).num_days() })
+            })
+            .collect();
+        print_json(&Value::Array(objects));
+        return Ok(());
+    }
+    for project in projects {
+        let due = project.due_date.expect("only projects with a due date are listed");
+        let days = (due - today).num_days();
+        let relative = match days {
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            d if d < 0 => format!("overdue by {} day{}", -d, if d == -1 { "" } else { "s" }),
+            d => format!("in {} days", d),
+        };
+        println!("{}  {:<18}  {}", due, relative, project.name);
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{data_dir, Error};
+
+/// Environment variable with the path of the config file to use instead of
+/// `~/.config/whisk/config.json`.
+const CONFIG_ENV: &str = "WHISK_CONFIG";
+
+/// The config file given with `--config`, which wins over `WHISK_CONFIG`.
+static PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// User settings, read from `~/.config/whisk/config.json`. Every field is optional.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Config {
+    /// Capture the mouse for clicking and scrolling in the UI.
+    pub mouse: bool,
+    /// Name of the built-in theme to start from.
+    pub theme: String,
+    /// Per-role color overrides applied on top of the theme, e.g. `{"accent": "Cyan"}`.
+    pub colors: serde_json::Map<String, serde_json::Value>,
+    /// Draw borders and symbols with plain ASCII, for terminals that garble box-drawing characters.
+    pub ascii: bool,
+    /// Show language badges as Nerd Font icons, for terminals using a patched font.
+    pub nerd_font: bool,
+    /// Command for `whisk open`, like `code` or `code --new-window {}`, with `{}` standing
+    /// for the project directory. Without it, `$VISUAL` or `$EDITOR` is used.
+    pub editor: Option<String>,
+    /// Directories searched for new projects by `whisk discover` and the UI, like `~/dev`.
+    pub project_roots: Vec<String>,
+    /// Where `whisk clone` and `whisk import` from a forge put repositories, the first of
+    /// `project_roots` if unset.
+    pub clone_root: Option<String>,
+    /// Token for the GitHub API in `whisk import github`, unless `$GITHUB_TOKEN` is set.
+    /// Private repositories are only listed with one.
+    pub github_token: Option<String>,
+    /// Token for `whisk import gitlab`, unless `$GITLAB_TOKEN` is set.
+    pub gitlab_token: Option<String>,
+    /// Token for `whisk import gitea`, unless `$GITEA_TOKEN` is set.
+    pub gitea_token: Option<String>,
+    /// How the directory of a new project is chosen: `built-in`, `xplr`, `fzf` or `broot`.
+    /// The built-in browser is used when the tool isn't available.
+    pub picker: String,
+    /// The windows of the sessions `whisk export tmuxinator` and `tmuxp` write, a single
+    /// shell if there are none.
+    pub tmux_windows: Vec<TmuxWindow>,
+    /// The apps 'O' in the UI offers to open the selected project with, besides the file
+    /// manager.
+    pub launchers: Vec<Launcher>,
+    /// Shell commands run when projects are added, removed or opened.
+    pub hooks: Hooks,
+    /// Desktop notifications when background tasks, `whisk exec` and `whisk discover`
+    /// finish after a while, and for projects due today or overdue when the UI starts.
+    pub notifications: bool,
+}
+
+/// A window in the sessions `whisk export` writes for projects.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct TmuxWindow {
+    pub name: String,
+    /// A tmux layout like `main-vertical`, tmux's default if unset.
+    pub layout: Option<String>,
+    /// The command each pane runs, an empty one for just a shell.
+    pub panes: Vec<String>,
+}
+
+/// An app in the "Open with" menu, like VS Code or lazygit.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Launcher {
+    pub name: String,
+    /// Like `code {}` or `lazygit -p {}`, with `{}` standing for the project directory,
+    /// which goes at the end without one.
+    pub command: String,
+    /// Whether the app runs in the terminal, like lazygit, which whisk hands over until
+    /// it exits. Others are started in the background.
+    pub terminal: bool,
+}
+
+/// Shell commands run in the background when something happens to a project, with the
+/// project in `WHISK_PROJECT_NAME`, `WHISK_PROJECT_DIR` and `WHISK_PROJECT_ID`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct Hooks {
+    pub add: Option<String>,
+    pub remove: Option<String>,
+    /// Run whenever a project is opened, also by `whisk path` and the picker.
+    pub open: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            mouse: true,
+            theme: "default".to_string(),
+            colors: serde_json::Map::new(),
+            ascii: false,
+            nerd_font: false,
+            editor: None,
+            project_roots: Vec::new(),
+            clone_root: None,
+            github_token: None,
+            gitlab_token: None,
+            gitea_token: None,
+            picker: "built-in".to_string(),
+            tmux_windows: Vec::new(),
+            launchers: Vec::new(),
+            hooks: Hooks::default(),
+            notifications: false,
+        }
+    }
+}
+
+/// Makes `path` the config file for the rest of the run.
+pub fn set_path(path: PathBuf) {
+    let _ = PATH.set(path);
+}
+
+pub fn config_path() -> PathBuf {
+    if let Some(path) = PATH.get() {
+        return path.clone();
+    }
+    if let Some(path) = std::env::var_os(CONFIG_ENV).filter(|path| !path.is_empty()) {
+        return PathBuf::from(path);
+    }
+    data_dir().join("config.json")
+}
+
+/// Loads the config file, falling back to the defaults when there's none.
+pub fn load() -> Result<Config, Error> {
+    let path = config_path();
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(Error::ParseConfigError)
+}
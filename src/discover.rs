@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tui::{
+    text::{Span, Spans},
+    widgets::{Block, BorderType, Borders, List, ListItem, ListState},
+};
+
+use crate::languages;
+use crate::search::SKIPPED_DIRECTORIES;
+use crate::theme::Theme;
+
+/// How many levels below a root are searched unless told otherwise.
+pub const DEFAULT_DEPTH: usize = 3;
+
+/// Whether `directory` looks like a project: a repository, or something with a build file.
+pub fn looks_like_project(directory: &Path) -> bool {
+    directory.join(".git").exists() || !languages::detect(&directory.to_string_lossy()).is_empty()
+}
+
+/// The directories in `roots` and up to `depth` levels below them that look like
+/// projects, sorted. The insides of a project aren't searched, nor hidden and build
+/// directories. Stops early with what it found once `cancelled` says so.
+pub fn discover(roots: &[PathBuf], depth: usize, cancelled: impl Fn() -> bool) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut pending: Vec<(PathBuf, usize)> = roots.iter().map(|root| (root.clone(), 0)).collect();
+    while let Some((dir, level)) = pending.pop() {
+        if cancelled() {
+            break;
+        }
+        if looks_like_project(&dir) {
+            found.push(dir);
+            continue;
+        }
+        if level == depth {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            if is_dir && !name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                pending.push((entry.path(), level + 1));
+            }
+        }
+    }
+    found.sort();
+    found.dedup();
+    found
+}
+
+/// The directories found by `discover`, to choose which to add.
+pub struct DiscoveredList {
+    directories: Vec<PathBuf>,
+    chosen: Vec<bool>,
+    pub state: ListState,
+}
+
+impl DiscoveredList {
+    /// All directories start out chosen.
+    pub fn new(directories: Vec<PathBuf>) -> DiscoveredList {
+        let mut state = ListState::default();
+        state.select(if directories.is_empty() { None } else { Some(0) });
+        DiscoveredList {
+            chosen: vec![true; directories.len()],
+            directories,
+            state,
+        }
+    }
+
+    pub fn next(&mut self) {
+        if let Some(selected) = self.state.selected() {
+            self.state.select(Some((selected + 1) % self.directories.len()));
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if let Some(selected) = self.state.selected() {
+            let len = self.directories.len();
+            self.state.select(Some((selected + len - 1) % len));
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if let Some(selected) = self.state.selected() {
+            self.chosen[selected] = !self.chosen[selected];
+        }
+    }
+
+    /// Chooses all directories, or none if all are chosen already.
+    pub fn toggle_all(&mut self) {
+        let all = self.chosen.iter().all(|&chosen| chosen);
+        self.chosen.iter_mut().for_each(|chosen| *chosen = !all);
+    }
+
+    pub fn chosen(&self) -> Vec<PathBuf> {
+        self.directories
+            .iter()
+            .zip(&self.chosen)
+            .filter(|(_, &chosen)| chosen)
+            .map(|(directory, _)| directory.clone())
+            .collect()
+    }
+
+    pub fn render<'a>(&self, theme: &Theme) -> List<'a> {
+        let items: Vec<_> = self
+            .directories
+            .iter()
+            .zip(&self.chosen)
+            .map(|(directory, &chosen)| {
+                let marker = if chosen { "[x] " } else { "[ ] " };
+                ListItem::new(Spans::from(vec![
+                    Span::styled(marker, theme.muted()),
+                    Span::raw(directory.display().to_string()),
+                ]))
+            })
+            .collect();
+
+        let chosen = self.chosen.iter().filter(|&&chosen| chosen).count();
+        List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(theme.text())
+                    .border_style(theme.border(true))
+                    .title(format!("Found {} new projects, {} chosen", self.directories.len(), chosen))
+                    .border_type(BorderType::Plain),
+            )
+            .highlight_style(theme.selection())
+    }
+}
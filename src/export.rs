@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use dirs::home_dir;
+
+use crate::config::TmuxWindow;
+use crate::{tmux, Project};
+
+/// A tool that starts tmux sessions from config files, one per project.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Tmuxinator,
+    Tmuxp,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Format> {
+        match name {
+            "tmuxinator" => Some(Format::Tmuxinator),
+            "tmuxp" => Some(Format::Tmuxp),
+            _ => None,
+        }
+    }
+
+    /// Where the tool looks for its configs.
+    pub fn default_dir(self) -> PathBuf {
+        let home = home_dir().unwrap_or_default();
+        match self {
+            Format::Tmuxinator => home.join(".config").join("tmuxinator"),
+            Format::Tmuxp => home.join(".tmuxp"),
+        }
+    }
+
+    /// The file of the project's session, named after the session like `whisk tmux` names it.
+    pub fn file_name(self, project: &Project) -> String {
+        format!("{}.yml", tmux::session_name(&project.name))
+    }
+
+    /// The YAML config of the project's session, with `windows` or a single shell.
+    pub fn render(self, project: &Project, windows: &[TmuxWindow]) -> String {
+        let shell = [TmuxWindow { name: "shell".to_string(), ..TmuxWindow::default() }];
+        let windows = if windows.is_empty() { &shell[..] } else { windows };
+        let name = quote(&tmux::session_name(&project.name));
+        let root = quote(&project.directory);
+
+        let mut yaml = format!("# Written by `whisk export` for the project '{}'.\n", project.name);
+        match self {
+            Format::Tmuxinator => yaml += &format!("name: {}\nroot: {}\nwindows:\n", name, root),
+            Format::Tmuxp => yaml += &format!("session_name: {}\nstart_directory: {}\nwindows:\n", name, root),
+        }
+        for window in windows {
+            // tmuxinator keys a window by its name, tmuxp names it in a field.
+            let indent = match self {
+                Format::Tmuxinator => {
+                    yaml += &format!("  - {}:\n", quote(&window.name));
+                    "      "
+                }
+                Format::Tmuxp => {
+                    yaml += &format!("  - window_name: {}\n", quote(&window.name));
+                    "    "
+                }
+            };
+            if let Some(layout) = &window.layout {
+                yaml += &format!("{}layout: {}\n", indent, quote(layout));
+            }
+            yaml += &format!("{}panes:\n", indent);
+            if window.panes.is_empty() {
+                yaml += &format!("{}  - \"\"\n", indent);
+            }
+            for pane in &window.panes {
+                yaml += &format!("{}  - {}\n", indent, quote(pane));
+            }
+        }
+        yaml
+    }
+}
+
+/// A double-quoted YAML string, which takes the same escapes as JSON.
+fn quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
@@ -0,0 +1,279 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+use crate::config::Config;
+
+/// The forges `whisk import` knows.
+pub const NAMES: [&str; 3] = ["github", "gitlab", "gitea"];
+
+/// A repository on a forge, whatever the forge calls its fields.
+pub struct Repo {
+    pub name: String,
+    pub clone_url: String,
+    pub ssh_url: String,
+    pub web_url: String,
+    pub description: Option<String>,
+    pub fork: bool,
+    pub archived: bool,
+    pub private: bool,
+}
+
+impl Repo {
+    /// Whether `url` is one of the URLs of the repository.
+    pub fn has_url(&self, url: &str) -> bool {
+        let url = url.trim_end_matches('/').trim_end_matches(".git");
+        [&self.clone_url, &self.ssh_url, &self.web_url]
+            .iter()
+            .any(|own| own.trim_end_matches(".git") == url)
+    }
+}
+
+/// A site hosting repositories whose API lists the repositories of a user.
+pub trait Forge {
+    fn name(&self) -> &'static str;
+
+    /// The token for the API: the environment variable of the forge, or its token in
+    /// the config.
+    fn token(&self, config: &Config) -> Option<String>;
+
+    /// The repositories `user` owns, sorted by name. The private ones are only listed
+    /// with a token that may see them. The error is what to tell the user.
+    fn user_repos(&self, user: &str, token: Option<&str>) -> Result<Vec<Repo>, String>;
+}
+
+/// The forge called `name`, at `url` for a self-hosted one. Gitea has no main site, so
+/// it needs one. The error is what to tell the user.
+pub fn find(name: &str, url: Option<&str>) -> Result<Box<dyn Forge>, String> {
+    let url = url.map(|url| url.trim_end_matches('/').to_string());
+    match name {
+        "github" => Ok(Box::new(GitHub {
+            // GitHub Enterprise serves the API below the site.
+            api: url.map_or_else(|| "https://api.github.com".to_string(), |url| format!("{}/api/v3", url)),
+        })),
+        "gitlab" => Ok(Box::new(GitLab {
+            api: format!("{}/api/v4", url.as_deref().unwrap_or("https://gitlab.com")),
+        })),
+        "gitea" => match url {
+            Some(url) => Ok(Box::new(Gitea { api: format!("{}/api/v1", url) })),
+            None => Err("gitea needs the URL of the site, like --url https://codeberg.org".to_string()),
+        },
+        other => Err(format!("unknown forge '{}', expected one of {}", other, NAMES.join(", "))),
+    }
+}
+
+/// github.com, or GitHub Enterprise.
+pub struct GitHub {
+    api: String,
+}
+
+/// A repository as GitHub and Gitea list it.
+#[derive(Deserialize)]
+struct GitHubRepo {
+    name: String,
+    clone_url: String,
+    ssh_url: String,
+    html_url: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    private: bool,
+}
+
+impl From<GitHubRepo> for Repo {
+    fn from(repo: GitHubRepo) -> Repo {
+        Repo {
+            name: repo.name,
+            clone_url: repo.clone_url,
+            ssh_url: repo.ssh_url,
+            web_url: repo.html_url,
+            // Gitea gives an empty description rather than none.
+            description: repo.description.filter(|description| !description.is_empty()),
+            fork: repo.fork,
+            archived: repo.archived,
+            private: repo.private,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct GitHubUser {
+    login: String,
+}
+
+impl Forge for GitHub {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn token(&self, config: &Config) -> Option<String> {
+        token_from("GITHUB_TOKEN", &config.github_token)
+    }
+
+    fn user_repos(&self, user: &str, token: Option<&str>) -> Result<Vec<Repo>, String> {
+        let auth = token.map(|token| format!("Authorization: Bearer {}", token));
+        // Only the user's own token lists private repositories, on a different endpoint.
+        let own = match &auth {
+            Some(auth) => {
+                let me: GitHubUser = get(&format!("{}/user", self.api), Some(auth))?;
+                me.login.eq_ignore_ascii_case(user)
+            }
+            None => false,
+        };
+        let url = if own {
+            format!("{}/user/repos?affiliation=owner&per_page={{}}&page={{}}", self.api)
+        } else {
+            format!("{}/users/{}/repos?type=owner&per_page={{}}&page={{}}", self.api, user)
+        };
+        paged::<GitHubRepo>(&url, 100, auth.as_deref())
+    }
+}
+
+/// gitlab.com, or a self-hosted GitLab.
+pub struct GitLab {
+    api: String,
+}
+
+#[derive(Deserialize)]
+struct GitLabProject {
+    path: String,
+    http_url_to_repo: String,
+    ssh_url_to_repo: String,
+    web_url: String,
+    #[serde(default)]
+    description: Option<String>,
+    /// Only there for forks.
+    #[serde(default)]
+    forked_from_project: Option<serde_json::Value>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    visibility: String,
+}
+
+impl From<GitLabProject> for Repo {
+    fn from(project: GitLabProject) -> Repo {
+        Repo {
+            // The path is what the repository is cloned as, the name may have spaces.
+            name: project.path,
+            clone_url: project.http_url_to_repo,
+            ssh_url: project.ssh_url_to_repo,
+            web_url: project.web_url,
+            description: project.description.filter(|description| !description.is_empty()),
+            fork: project.forked_from_project.is_some_and(|parent| !parent.is_null()),
+            archived: project.archived,
+            private: project.visibility == "private",
+        }
+    }
+}
+
+impl Forge for GitLab {
+    fn name(&self) -> &'static str {
+        "gitlab"
+    }
+
+    fn token(&self, config: &Config) -> Option<String> {
+        token_from("GITLAB_TOKEN", &config.gitlab_token)
+    }
+
+    fn user_repos(&self, user: &str, token: Option<&str>) -> Result<Vec<Repo>, String> {
+        let auth = token.map(|token| format!("PRIVATE-TOKEN: {}", token));
+        let url = format!("{}/users/{}/projects?per_page={{}}&page={{}}", self.api, user);
+        paged::<GitLabProject>(&url, 100, auth.as_deref())
+    }
+}
+
+/// A Gitea or Forgejo site, like codeberg.org.
+pub struct Gitea {
+    api: String,
+}
+
+impl Forge for Gitea {
+    fn name(&self) -> &'static str {
+        "gitea"
+    }
+
+    fn token(&self, config: &Config) -> Option<String> {
+        token_from("GITEA_TOKEN", &config.gitea_token)
+    }
+
+    fn user_repos(&self, user: &str, token: Option<&str>) -> Result<Vec<Repo>, String> {
+        let auth = token.map(|token| format!("Authorization: token {}", token));
+        let url = format!("{}/users/{}/repos?limit={{}}&page={{}}", self.api, user);
+        paged::<GitHubRepo>(&url, 50, auth.as_deref())
+    }
+}
+
+fn token_from(variable: &str, config: &Option<String>) -> Option<String> {
+    std::env::var(variable)
+        .ok()
+        .filter(|token| !token.is_empty())
+        .or_else(|| config.clone())
+}
+
+/// Fetches the pages of a list until one is empty, sorted by name. `url` has a `{}`
+/// for the page size and one for the page. Sites may return fewer than asked for, like
+/// Gitea's default of at most 50, so a page that isn't full isn't the last one.
+fn paged<T: DeserializeOwned + Into<Repo>>(url: &str, per_page: usize, auth: Option<&str>) -> Result<Vec<Repo>, String> {
+    let mut repos = Vec::new();
+    for page in 1.. {
+        let page_url = url.replacen("{}", &per_page.to_string(), 1).replacen("{}", &page.to_string(), 1);
+        let batch: Vec<T> = get(&page_url, auth)?;
+        if batch.is_empty() {
+            break;
+        }
+        repos.extend(batch.into_iter().map(Into::into));
+    }
+    repos.sort_by_key(|repo: &Repo| repo.name.to_lowercase());
+    Ok(repos)
+}
+
+/// Fetches `url` with curl and parses the JSON. The header with the token is handed over
+/// on stdin, so it doesn't show up in the process list.
+fn get<T: DeserializeOwned>(url: &str, auth: Option<&str>) -> Result<T, String> {
+    let mut child = Command::new("curl")
+        .args(["--silent", "--show-error", "--location"])
+        .args(["--header", "Accept: application/json"])
+        .args(["--header", "User-Agent: whisk"])
+        .args(["--header", "@-"])
+        .args(["--write-out", "\n%{http_code}"])
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("can't run curl: {}", err))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Some(auth) = auth {
+            let _ = writeln!(stdin, "{}", auth);
+        }
+    }
+    let output = child.wait_with_output().map_err(|err| format!("curl failed: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("curl failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (body, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    if status.trim() != "200" {
+        // Errors come with a message, like "Not Found" or "Bad credentials".
+        let message = serde_json::from_str::<serde_json::Value>(body)
+            .ok()
+            .and_then(|error| error["message"].as_str().or(error["error"].as_str()).map(str::to_string))
+            .unwrap_or_else(|| format!("HTTP status {}", status.trim()));
+        return Err(format!("{} says: {}", host(url), message));
+    }
+    serde_json::from_str(body).map_err(|err| format!("can't read the answer of {}: {}", host(url), err))
+}
+
+/// The host part of `url`, for messages.
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split('/').next().unwrap_or(rest)
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, TimeZone, Utc};
+
+/// A remote configured in a repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Remote {
+    pub name: String,
+    pub url: String,
+}
+
+impl Remote {
+    /// The URL to open in a browser: SSH URLs like `git@github.com:user/repo.git`
+    /// become `https://github.com/user/repo`.
+    pub fn web_url(&self) -> String {
+        web_url(&self.url)
+    }
+}
+
+/// Reads the remotes from the project's `.git/config`, in the order they're configured.
+pub fn remotes(directory: &str) -> Vec<Remote> {
+    let Ok(config) = fs::read_to_string(Path::new(directory).join(".git").join("config")) else {
+        return Vec::new();
+    };
+
+    let mut current_remote: Option<String> = None;
+    let mut remotes = Vec::new();
+
+    for line in config.lines().map(str::trim) {
+        if line.starts_with('[') {
+            current_remote = line
+                .strip_prefix("[remote \"")
+                .and_then(|rest| rest.strip_suffix("\"]"))
+                .map(str::to_string);
+            continue;
+        }
+
+        let Some(remote) = &current_remote else {
+            continue;
+        };
+        if let Some((key, value)) = line.split_once('=') {
+            // Only the first URL counts, further ones are push mirrors.
+            if key.trim() == "url" && !remotes.iter().any(|r: &Remote| r.name == *remote) {
+                remotes.push(Remote {
+                    name: remote.clone(),
+                    url: value.trim().to_string(),
+                });
+            }
+        }
+    }
+
+    remotes
+}
+
+/// Reads the URL of the `origin` remote (or the first remote if there's no origin)
+/// from the project's `.git/config`.
+pub fn detect_remote_url(directory: &str) -> Option<String> {
+    let remotes = remotes(directory);
+    remotes
+        .iter()
+        .find(|remote| remote.name == "origin")
+        .or(remotes.first())
+        .map(|remote| remote.url.clone())
+}
+
+/// Turns a clone URL into one a browser can open, see `Remote::web_url`. The user or
+/// token in a URL and the port of an SSH or git URL are left out.
+pub fn web_url(url: &str) -> String {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url);
+    if let Some((scheme, rest)) = url.split_once("://") {
+        let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+        let host = authority.rsplit_once('@').map_or(authority, |(_, host)| host);
+        return match scheme {
+            "http" | "https" => format!("{}://{}/{}", scheme, host, path),
+            _ => format!("https://{}/{}", host.split(':').next().unwrap_or(host), path),
+        };
+    }
+    match url.split_once('@').and_then(|(_, rest)| rest.split_once(':')) {
+        Some((host, path)) if !url.contains("://") => format!("https://{}/{}", host, path),
+        _ => url.to_string(),
+    }
+}
+
+/// The name of the repository at a clone URL, like `whisk` for
+/// `git@github.com:user/whisk.git`.
+pub fn repo_name(url: &str) -> Option<&str> {
+    let url = url.trim_end_matches('/');
+    let url = url.strip_suffix(".git").unwrap_or(url).trim_end_matches('/');
+    let name = url.rsplit(['/', ':']).next()?;
+    (!name.is_empty()).then_some(name)
+}
+
+/// Runs `git clone`, showing its progress. The error is what to tell the user.
+pub fn clone(url: &str, target: &Path) -> Result<(), String> {
+    let status = Command::new("git")
+        .arg("clone")
+        .arg(url)
+        .arg(target)
+        .status()
+        .map_err(|err| format!("can't run git: {}", err))?;
+    if !status.success() {
+        return Err(format!("git clone failed for {}", url));
+    }
+    Ok(())
+}
+
+/// The working tree state of a repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitStatus {
+    pub branch: String,
+    pub ahead: u32,
+    pub behind: u32,
+    /// Files with uncommitted changes, untracked ones included.
+    pub changed: u32,
+    /// `None` in a repository without commits.
+    pub last_commit: Option<Commit>,
+}
+
+/// The most recent commit of the checked out branch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub author: String,
+    pub subject: String,
+    pub committed_at: DateTime<Utc>,
+}
+
+impl GitStatus {
+    /// Compact form for the UI, e.g. `main ↑2 ↓1 *`.
+    pub fn summary(&self) -> String {
+        let mut summary = self.branch.clone();
+        if self.ahead > 0 {
+            summary.push_str(&format!(" ↑{}", self.ahead));
+        }
+        if self.behind > 0 {
+            summary.push_str(&format!(" ↓{}", self.behind));
+        }
+        if self.is_dirty() {
+            summary.push_str(" *");
+        }
+        summary
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.changed > 0
+    }
+
+    /// Whether something is left to commit, push or pull.
+    pub fn needs_attention(&self) -> bool {
+        self.is_dirty() || self.ahead > 0 || self.behind > 0
+    }
+}
+
+/// Runs `git status` in `directory`. `None` if it isn't a repository or git isn't installed.
+pub fn status(directory: &str) -> Option<GitStatus> {
+    let output = Command::new("git")
+        .args(["status", "--porcelain=v2", "--branch"])
+        .current_dir(directory)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut status = GitStatus {
+        branch: String::new(),
+        ahead: 0,
+        behind: 0,
+        changed: 0,
+        last_commit: last_commit(directory),
+    };
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(head) = line.strip_prefix("# branch.head ") {
+            status.branch = head.to_string();
+        } else if let Some(counts) = line.strip_prefix("# branch.ab ") {
+            for count in counts.split_whitespace() {
+                if let Some(ahead) = count.strip_prefix('+') {
+                    status.ahead = ahead.parse().unwrap_or(0);
+                } else if let Some(behind) = count.strip_prefix('-') {
+                    status.behind = behind.parse().unwrap_or(0);
+                }
+            }
+        } else if !line.starts_with('#') {
+            status.changed += 1;
+        }
+    }
+    Some(status)
+}
+
+/// Runs `git log` in `directory` for the latest commit.
+fn last_commit(directory: &str) -> Option<Commit> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%h%x00%an%x00%ct%x00%s"])
+        .current_dir(directory)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.trim_end().splitn(4, '\0');
+    let hash = fields.next()?.to_string();
+    let author = fields.next()?.to_string();
+    let committed_at = Utc.timestamp_opt(fields.next()?.parse().ok()?, 0).single()?;
+    let subject = fields.next().unwrap_or_default().to_string();
+    Some(Commit {
+        hash,
+        author,
+        subject,
+        committed_at,
+    })
+}
+
+/// How long a looked-up status is shown before it's refreshed.
+const STATUS_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Default)]
+struct CacheEntry {
+    status: Option<GitStatus>,
+    /// When the last lookup finished, `None` before the first one.
+    fetched_at: Option<Instant>,
+    pending: bool,
+}
+
+/// Looks up git statuses on a background thread so a slow repository doesn't block the UI.
+pub struct StatusCache {
+    requests: Sender<String>,
+    results: Receiver<(String, Option<GitStatus>)>,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl StatusCache {
+    pub fn new() -> StatusCache {
+        let (requests, pending) = mpsc::channel::<String>();
+        let (done, results) = mpsc::channel();
+        thread::spawn(move || {
+            for directory in pending {
+                let status = status(&directory);
+                if done.send((directory, status)).is_err() {
+                    break;
+                }
+            }
+        });
+        StatusCache {
+            requests,
+            results,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// The last known status of `directory`, asking for a fresh one if it's missing or old.
+    pub fn get(&mut self, directory: &str) -> Option<&GitStatus> {
+        for (dir, status) in self.results.try_iter() {
+            let entry = self.entries.entry(dir).or_default();
+            entry.status = status;
+            entry.fetched_at = Some(Instant::now());
+            entry.pending = false;
+        }
+
+        let entry = self.entries.entry(directory.to_string()).or_default();
+        let stale = entry.fetched_at.is_none_or(|fetched_at| fetched_at.elapsed() >= STATUS_TTL);
+        // The old status stays visible until the new one arrives.
+        if stale && !entry.pending && self.requests.send(directory.to_string()).is_ok() {
+            entry.pending = true;
+        }
+        entry.status.as_ref()
+    }
+}
@@ -0,0 +1,52 @@
+use std::process::Stdio;
+use std::thread;
+
+use crate::{config, script_command, Project};
+
+/// What happened to a project, for the hooks of the config.
+#[derive(Clone, Copy)]
+pub enum Event {
+    Add,
+    Remove,
+    Open,
+}
+
+impl Event {
+    fn name(self) -> &'static str {
+        match self {
+            Event::Add => "add",
+            Event::Remove => "remove",
+            Event::Open => "open",
+        }
+    }
+}
+
+/// Runs the hook of the config for `event` with the shell, in the background, with the
+/// project in `WHISK_PROJECT_NAME`, `WHISK_PROJECT_DIR` and `WHISK_PROJECT_ID` and the
+/// event in `WHISK_EVENT`. Its output would garble the UI, so it's dropped, and a hook
+/// that fails doesn't keep whisk from going on.
+pub fn run(event: Event, project: &Project) {
+    let Ok(config) = config::load() else {
+        return;
+    };
+    let hook = match event {
+        Event::Add => config.hooks.add,
+        Event::Remove => config.hooks.remove,
+        Event::Open => config.hooks.open,
+    };
+    let Some(hook) = hook.filter(|hook| !hook.trim().is_empty()) else {
+        return;
+    };
+    let started = script_command(&hook)
+        .env("WHISK_EVENT", event.name())
+        .env("WHISK_PROJECT_NAME", &project.name)
+        .env("WHISK_PROJECT_DIR", &project.directory)
+        .env("WHISK_PROJECT_ID", &project.id)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = started {
+        thread::spawn(move || child.wait());
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A language or tool recognized by a file in the root of a project.
+pub struct Language {
+    pub name: &'static str,
+    /// Short label for the project list.
+    badge: &'static str,
+    /// Nerd Font glyph used instead of the badge when `nerd_font` is configured.
+    icon: &'static str,
+    /// Files whose presence means the project uses it.
+    markers: &'static [&'static str],
+}
+
+impl Language {
+    pub fn badge(&self, nerd_font: bool) -> &'static str {
+        if nerd_font {
+            self.icon
+        } else {
+            self.badge
+        }
+    }
+}
+
+const LANGUAGES: [Language; 16] = [
+    Language { name: "Rust", badge: "rs", icon: "\u{e7a8}", markers: &["Cargo.toml"] },
+    Language { name: "TypeScript", badge: "ts", icon: "\u{e628}", markers: &["tsconfig.json"] },
+    Language { name: "Node.js", badge: "js", icon: "\u{e718}", markers: &["package.json"] },
+    Language { name: "Deno", badge: "deno", icon: "\u{e7c0}", markers: &["deno.json", "deno.jsonc"] },
+    Language {
+        name: "Python",
+        badge: "py",
+        icon: "\u{e73c}",
+        markers: &["pyproject.toml", "setup.py", "requirements.txt", "Pipfile"],
+    },
+    Language { name: "Go", badge: "go", icon: "\u{e626}", markers: &["go.mod"] },
+    Language { name: "Ruby", badge: "rb", icon: "\u{e739}", markers: &["Gemfile"] },
+    Language { name: "Java", badge: "java", icon: "\u{e738}", markers: &["pom.xml", "build.gradle", "build.gradle.kts"] },
+    Language { name: "PHP", badge: "php", icon: "\u{e73d}", markers: &["composer.json"] },
+    Language { name: "Elixir", badge: "ex", icon: "\u{e62d}", markers: &["mix.exs"] },
+    Language { name: "Dart", badge: "dart", icon: "\u{e798}", markers: &["pubspec.yaml"] },
+    Language { name: "Swift", badge: "swift", icon: "\u{e755}", markers: &["Package.swift"] },
+    Language { name: "Zig", badge: "zig", icon: "\u{e6a9}", markers: &["build.zig"] },
+    Language { name: "C/C++", badge: "c", icon: "\u{e61d}", markers: &["CMakeLists.txt", "meson.build"] },
+    Language { name: "Nix", badge: "nix", icon: "\u{f313}", markers: &["flake.nix", "default.nix", "shell.nix"] },
+    Language { name: "Docker", badge: "docker", icon: "\u{f308}", markers: &["Dockerfile", "compose.yaml", "docker-compose.yml"] },
+];
+
+/// The languages and tools used in `directory`, judging by the files in its root.
+pub fn detect(directory: &str) -> Vec<&'static Language> {
+    let directory = Path::new(directory);
+    LANGUAGES
+        .iter()
+        .filter(|language| language.markers.iter().any(|marker| directory.join(marker).exists()))
+        .collect()
+}
+
+/// The languages of project directories, detected once rather than on every frame.
+#[derive(Default)]
+pub struct Cache {
+    detected: HashMap<String, Vec<&'static Language>>,
+}
+
+impl Cache {
+    pub fn get(&mut self, directory: &str) -> Vec<&'static Language> {
+        self.detected.entry(directory.to_string()).or_insert_with(|| detect(directory)).clone()
+    }
+
+    /// Forgets what was detected, for when the files may have changed.
+    pub fn clear(&mut self) {
+        self.detected.clear();
+    }
+}
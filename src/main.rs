@@ -1,30 +1,73 @@
 use chrono::prelude::*;
 use crossterm::{
-    event::{self, Event as CEvent, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event as CEvent, KeyCode, KeyModifiers, MouseEvent,
+    },
+    execute,
     terminal::{disable_raw_mode, enable_raw_mode},
 };
 use dirs::home_dir;
+use fuzzy_matcher::{skim::SkimMatcherV2, FuzzyMatcher};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use std::{fs::{self, File}, path::{PathBuf, Path}, sync::Arc};
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}, sync::Arc};
 use std::io;
 use std::io::prelude::*;
 use std::sync::mpsc;
 use std::thread;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
+use std::process::{Command, Stdio};
+use app::{App, Foreground};
+use theme::Theme;
 use thiserror::Error;
 use tui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout},
-    style::{Color, Modifier, Style},
-    text::{Span, Spans},
+    layout::{Constraint, Rect},
+    style::{Modifier, Style},
+    text::{Span, Spans, Text},
     widgets::{
-        Block, BorderType, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, Tabs,
+        Block, BorderType, Borders, Cell, List, ListItem, ListState, Paragraph, Row, Table, Wrap,
     },
     Terminal,
 };
 
+mod app;
+mod ascii;
+mod backup;
+mod browser;
+mod cli;
+mod config;
+mod discover;
+mod export;
+mod forge;
+mod git;
+mod hooks;
+mod languages;
+mod markdown;
+mod notify;
+mod picker;
+mod search;
+mod state;
+mod tasks;
+mod theme;
+mod tmux;
+mod usage;
+
 const DB_PATH: &str = "/.config/whisk";
+/// Environment variable with the path of the DB file to use instead of `~/.config/whisk/db.json`.
+const DB_ENV: &str = "WHISK_DB";
+/// A file next to the executable that turns on portable mode, see `data_dir`.
+const PORTABLE_MARKER: &str = "whisk-portable";
+
+const SHORT_ID_LEN: usize = 7;
+
+/// Set by `--portable`.
+static PORTABLE: AtomicBool = AtomicBool::new(false);
+/// Set by `--read-only`: the DB isn't written, see `write_db`.
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+/// Set by `--fresh`: the UI doesn't restore the session of the last run.
+static FRESH: AtomicBool = AtomicBool::new(false);
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -32,11 +75,38 @@ pub enum Error {
     ReadDBError(#[from] io::Error),
     #[error("error parsing the DB file: {0}")]
     ParseDBError(#[from] serde_json::Error),
+    #[error("error parsing the config file: {0}")]
+    ParseConfigError(serde_json::Error),
+    #[error("no project matches '{0}'")]
+    ProjectNotFound(String),
+    #[error("'{0}' can't be nested under itself or one of its sub-projects")]
+    ParentCycle(String),
+    #[error("unknown theme '{0}'")]
+    UnknownTheme(String),
+    #[error("'{0}' is not an existing directory")]
+    NotADirectory(String),
+    #[error("'{0}' is not a valid date, expected YYYY-MM-DD")]
+    InvalidDate(String),
+    #[error("alias '{0}' is already used by another project")]
+    DuplicateAlias(String),
+    #[error("name '{0}' is already used by another project")]
+    DuplicateName(String),
+    #[error("alias '{0}' may only contain letters, digits, '-' and '_'")]
+    InvalidAlias(String),
+    #[error("the name can't be empty")]
+    EmptyName,
+    #[error("no backup '{0}', see `whisk restore` for the list")]
+    BackupNotFound(String),
+    #[error("can't change projects in read-only mode")]
+    ReadOnly,
 }
 
 enum Event<I> {
     Input(I),
+    Mouse(MouseEvent),
     Tick,
+    /// The input thread stopped reading the terminal, see `run_in_foreground`.
+    Paused,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -45,338 +115,3201 @@ struct Project {
     name: String,
     directory: String,
     created_at: DateTime<Utc>,
+    #[serde(default)]
+    status: Status,
+    #[serde(default)]
+    priority: Priority,
+    #[serde(default)]
+    repo_url: Option<String>,
+    #[serde(default)]
+    alias: Option<String>,
+    #[serde(default)]
+    parent_id: Option<String>,
+    #[serde(default)]
+    collapsed: bool,
+    #[serde(default)]
+    order: usize,
+    #[serde(default)]
+    due_date: Option<NaiveDate>,
+    #[serde(default)]
+    todos: Vec<TodoItem>,
+    #[serde(default)]
+    time_entries: Vec<TimeEntry>,
+    #[serde(default)]
+    links: Vec<ProjectLink>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    workspace: Option<String>,
+    #[serde(default)]
+    last_opened_at: Option<DateTime<Utc>>,
+    /// How often the project was opened, counted since this was added.
+    #[serde(default)]
+    open_count: u32,
+    #[serde(default)]
+    notes: String,
+    #[serde(default)]
+    disk_usage: Option<DiskUsage>,
+    /// Named shell commands, run in the project directory with `whisk run`.
+    #[serde(default)]
+    tasks: BTreeMap<String, String>,
+    /// Environment variables set for the project's tasks.
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    /// Opens the project instead of the editor of the config, like `code-insiders {dir}`.
+    #[serde(default)]
+    open_command: Option<String>,
 }
 
-#[derive(Copy, Clone, Debug)]
-enum MenuItem {
-    Home,
-    Projects,
+/// The size of a project directory when it was last measured.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+struct DiskUsage {
+    bytes: u64,
+    measured_at: DateTime<Utc>,
+}
+
+impl DiskUsage {
+    /// Whether the directory should be measured again, once a day.
+    fn is_stale(&self) -> bool {
+        Utc::now() - self.measured_at >= chrono::Duration::days(1)
+    }
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq)]
+enum LinkKind {
+    DependsOn,
+    RelatedTo,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct ProjectLink {
+    kind: LinkKind,
+    target_id: String,
+}
+
+/// A span of tracked work; `end` is `None` while the timer is running.
+#[derive(Serialize, Deserialize, Clone)]
+struct TimeEntry {
+    start: DateTime<Utc>,
+    end: Option<DateTime<Utc>>,
+}
+
+impl TimeEntry {
+    /// Time of this entry that falls inside `[from, to)`.
+    fn overlap(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> chrono::Duration {
+        let start = self.start.max(from);
+        let end = self.end.unwrap_or_else(Utc::now).min(to);
+        (end - start).max(chrono::Duration::zero())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+struct TodoItem {
+    text: String,
+    done: bool,
+}
+
+impl Project {
+    /// First characters of the UUID, enough to tell projects apart in the UI and on the command line.
+    fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(SHORT_ID_LEN)]
+    }
+
+    fn open_todos(&self) -> usize {
+        self.todos.iter().filter(|todo| !todo.done).count()
+    }
+
+    fn is_timer_running(&self) -> bool {
+        self.time_entries.iter().any(|entry| entry.end.is_none())
+    }
+
+    fn total_time(&self) -> chrono::Duration {
+        self.time_entries
+            .iter()
+            .map(|entry| entry.overlap(DateTime::<Utc>::MIN_UTC, DateTime::<Utc>::MAX_UTC))
+            .fold(chrono::Duration::zero(), |total, spent| total + spent)
+    }
+
+    /// How often and how lately the project was opened, ranked like zoxide ranks
+    /// directories: the times it was opened, weighted by how long ago the last time was.
+    fn frecency(&self, now: DateTime<Utc>) -> u64 {
+        let Some(opened) = self.last_opened_at else {
+            return 0;
+        };
+        let age = now - opened;
+        let weight = if age.num_hours() < 1 {
+            16
+        } else if age.num_days() < 1 {
+            8
+        } else if age.num_days() < 7 {
+            2
+        } else {
+            1
+        };
+        // Projects opened before the count was kept have a count of 0.
+        u64::from(self.open_count.max(1)) * weight
+    }
+
+    /// Whether the directory is gone from disk, e.g. after it was moved or deleted.
+    fn is_missing(&self) -> bool {
+        !Path::new(&self.directory).is_dir()
+    }
+
+    /// Past its due date while still being worked on.
+    fn is_overdue(&self) -> bool {
+        let open = matches!(self.status, Status::Active | Status::OnHold);
+        open && self.due_date.is_some_and(|due| due < Local::now().date_naive())
+    }
 }
 
-impl From<MenuItem> for usize {
-    fn from(input: MenuItem) -> usize {
-        match input {
-            MenuItem::Home => 0,
-            MenuItem::Projects => 1,
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+enum Status {
+    #[default]
+    Active,
+    OnHold,
+    Done,
+    Abandoned,
+    Archived,
+}
+
+impl Status {
+    const ALL: [Status; 5] = [
+        Status::Active,
+        Status::OnHold,
+        Status::Done,
+        Status::Abandoned,
+        Status::Archived,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Status::Active => "Active",
+            Status::OnHold => "On Hold",
+            Status::Done => "Done",
+            Status::Abandoned => "Abandoned",
+            Status::Archived => "Archived",
         }
     }
+
+    fn next(self) -> Status {
+        let index = Status::ALL.iter().position(|s| *s == self).unwrap_or(0);
+        Status::ALL[(index + 1) % Status::ALL.len()]
+    }
+
+    fn previous(self) -> Status {
+        let index = Status::ALL.iter().position(|s| *s == self).unwrap_or(0);
+        Status::ALL[(index + Status::ALL.len() - 1) % Status::ALL.len()]
+    }
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    enable_raw_mode().expect("can run in raw mode");
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+enum Priority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
 
-    let (tx, rx) = mpsc::channel();
-    let tick_rate = Duration::from_millis(200);
-    thread::spawn(move || {
-        let mut last_tick = Instant::now();
-        loop {
-            let timeout = tick_rate
-                .checked_sub(last_tick.elapsed())
-                .unwrap_or_else(|| Duration::from_secs(0));
+impl Priority {
+    fn indicator(self) -> &'static str {
+        match self {
+            Priority::High => "▲",
+            Priority::Medium => "■",
+            Priority::Low => "▼",
+        }
+    }
 
-            if event::poll(timeout).expect("poll works") {
-                if let CEvent::Key(key) = event::read().expect("can read events") {
-                    tx.send(Event::Input(key)).expect("can send events");
-                }
-            }
+    fn label(self) -> &'static str {
+        match self {
+            Priority::High => "High",
+            Priority::Medium => "Medium",
+            Priority::Low => "Low",
+        }
+    }
 
-            if last_tick.elapsed() >= tick_rate {
-                if let Ok(_) = tx.send(Event::Tick) {
-                    last_tick = Instant::now();
-                }
-            }
+    fn raise(self) -> Priority {
+        match self {
+            Priority::Low => Priority::Medium,
+            _ => Priority::High,
         }
-    });
+    }
 
-    let stdout = io::stdout();
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    terminal.clear()?;
+    fn lower(self) -> Priority {
+        match self {
+            Priority::High => Priority::Medium,
+            _ => Priority::Low,
+        }
+    }
+}
 
-    let menu_titles = vec!["Home", "Projects", "Add", "Delete", "Quit"];
-    let mut active_menu_item = MenuItem::Home;
-    let mut project_list_state = ListState::default();
-    project_list_state.select(Some(0));
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+enum SortMode {
+    #[default]
+    Added,
+    Name,
+    LastOpened,
+    Path,
+    Priority,
+    Manual,
+    DueDate,
+    Size,
+    Frecency,
+}
 
-    loop {
-        terminal.draw(|rect| {
-            let size = rect.size();
-            let chunks = Layout::default()
-                .direction(Direction::Vertical)
-                .margin(2)
-                .constraints(
-                    [
-                        Constraint::Length(3),
-                        Constraint::Min(2),
-                        Constraint::Length(3),
-                    ]
-                    .as_ref(),
-                )
-                .split(size);
-
-            let menu = menu_titles
-                .iter()
-                .map(|t| {
-                    let (first, rest) = t.split_at(1);
-                    Spans::from(vec![
-                        Span::styled(
-                            first,
-                            Style::default()
-                                .fg(Color::Yellow)
-                                .add_modifier(Modifier::UNDERLINED),
-                        ),
-                        Span::styled(rest, Style::default().fg(Color::White)),
-                    ])
-                })
-                .collect();
+impl SortMode {
+    const ALL: [SortMode; 9] = [
+        SortMode::Added,
+        SortMode::Name,
+        SortMode::LastOpened,
+        SortMode::Path,
+        SortMode::Priority,
+        SortMode::Manual,
+        SortMode::DueDate,
+        SortMode::Size,
+        SortMode::Frecency,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            SortMode::Added => "date added",
+            SortMode::Name => "name",
+            SortMode::LastOpened => "last opened",
+            SortMode::Path => "path",
+            SortMode::Priority => "priority",
+            SortMode::Manual => "manual",
+            SortMode::DueDate => "due date",
+            SortMode::Size => "size",
+            SortMode::Frecency => "frecency",
+        }
+    }
+}
+
+/// What the project list is split into sections by.
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+enum GroupBy {
+    #[default]
+    Nothing,
+    Tag,
+    Workspace,
+    /// A tree of the folders the projects are in.
+    Directory,
+}
+
+impl GroupBy {
+    fn next(self) -> GroupBy {
+        match self {
+            GroupBy::Nothing => GroupBy::Tag,
+            GroupBy::Tag => GroupBy::Workspace,
+            GroupBy::Workspace => GroupBy::Directory,
+            GroupBy::Directory => GroupBy::Nothing,
+        }
+    }
 
-            let tabs = Tabs::new(menu)
-                .select(active_menu_item.into())
-                .block(Block::default().title("Menu").borders(Borders::ALL))
-                .style(Style::default().fg(Color::White))
-                .highlight_style(Style::default().fg(Color::Yellow))
-                .divider(Span::raw("|"));
-
-            rect.render_widget(tabs, chunks[0]);
-            match active_menu_item {
-                MenuItem::Home => rect.render_widget(render_home(), chunks[1]),
-                MenuItem::Projects => {
-                    let projects_chunks = Layout::default()
-                        .direction(Direction::Horizontal)
-                        .constraints(
-                            [Constraint::Percentage(20), Constraint::Percentage(80)].as_ref(),
-                        )
-                        .split(chunks[1]);
-                    let (left, right) = render_projects(&project_list_state);
-                    rect.render_stateful_widget(left, projects_chunks[0], &mut project_list_state);
-                    rect.render_widget(right.unwrap(), projects_chunks[1]);
+    fn label(self) -> &'static str {
+        match self {
+            GroupBy::Nothing => "nothing",
+            GroupBy::Tag => "tag",
+            GroupBy::Workspace => "workspace",
+            GroupBy::Directory => "directory",
+        }
+    }
+
+    /// The section of `project` as a path of nested sections: its first tag, its workspace or
+    /// the folders it's in. Empty when not grouping.
+    fn group(self, project: &Project) -> Vec<String> {
+        match self {
+            GroupBy::Nothing => Vec::new(),
+            GroupBy::Tag => vec![project.tags.first().cloned().unwrap_or_else(|| "untagged".to_string())],
+            GroupBy::Workspace => vec![project.workspace.clone().unwrap_or_else(|| "no workspace".to_string())],
+            GroupBy::Directory => {
+                let parent = Path::new(&project.directory).parent().unwrap_or(Path::new("/"));
+                let parent = shorten_path(&parent.to_string_lossy(), usize::MAX);
+                let mut folders: Vec<String> = parent.split('/').filter(|f| !f.is_empty()).map(String::from).collect();
+                if !parent.starts_with('~') {
+                    folders.insert(0, "/".to_string());
                 }
+                folders
             }
-        })?;
+        }
+    }
 
-        match rx.recv()? {
-            Event::Input(event) => match event.code {
-                KeyCode::Char('q') => {
-                    disable_raw_mode()?;
-                    terminal.show_cursor()?;
-                    break;
-                }
-                KeyCode::Char('h') => active_menu_item = MenuItem::Home,
-                KeyCode::Char('p') => active_menu_item = MenuItem::Projects,
-                KeyCode::Char('a') => {
-                    match xplr::runner::runner().and_then(|a| a.run()) {
-                        Ok(Some(out)) => {
-                            let project_name = out
-                                .split('/')
-                                .next_back()
-                                .expect("There is a project name");
-
-                            add_project_to_db(project_name.to_string(), out.to_string()).expect("can add new project");
-                        },
-                        Ok(None) => {}
-                        Err(err) => {
-                            if !err.to_string().is_empty() {
-                                eprintln!("error: {}", err);
-                            };
-
-                            std::process::exit(1);
-                        }
-                    }
-                }
-                KeyCode::Char('d') => {
-                    remove_project_at_index(&mut project_list_state).expect("can remove project");
-                }
-                KeyCode::Down => {
-                    if let Some(selected) = project_list_state.selected() {
-                        let amount_projects = read_db().expect("can fetch project list").len();
-                        if selected >= amount_projects - 1 {
-                            project_list_state.select(Some(0));
-                        } else {
-                            project_list_state.select(Some(selected + 1));
-                        }
-                    }
-                }
-                KeyCode::Up => {
-                    if let Some(selected) = project_list_state.selected() {
-                        let amount_projects = read_db().expect("can fetch project list").len();
-                        if selected > 0 {
-                            project_list_state.select(Some(selected - 1));
-                        } else {
-                            project_list_state.select(Some(amount_projects - 1));
-                        }
-                    }
-                }
-                _ => {}
-            },
-            Event::Tick => {}
+    /// Whether `project` has a section of its own, rather than the catch-all one listed last.
+    fn has_own_group(self, project: &Project) -> bool {
+        match self {
+            GroupBy::Tag => !project.tags.is_empty(),
+            GroupBy::Workspace => project.workspace.is_some(),
+            GroupBy::Nothing | GroupBy::Directory => true,
         }
     }
 
-    Ok(())
+    /// What a section is collapsed by in `ListView::collapsed_groups`.
+    fn key(self, path: &[String]) -> String {
+        format!("{}:{}", self.label(), path.join("/"))
+    }
 }
 
-fn render_home<'a>() -> Paragraph<'a> {
-    let home = Paragraph::new(vec![
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Welcome")]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("to")]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::styled(
-            "whisk-CLI",
-            Style::default().fg(Color::LightBlue),
-        )]),
-        Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Press 'p' to access projects, 'a' to add a new project and 'd' to delete the currently selected project.")]),
-    ])
-    .alignment(Alignment::Center)
-    .block(
-        Block::default()
-            .borders(Borders::ALL)
-            .style(Style::default().fg(Color::White))
-            .title("Home")
-            .border_type(BorderType::Plain),
-    );
-    home
+/// How the project list is narrowed down and ordered.
+#[derive(Clone, Debug, Default)]
+struct ListView {
+    status_filter: Option<Status>,
+    /// Only projects of this workspace, when a workspace tab is active.
+    workspace: Option<String>,
+    sort_mode: SortMode,
+    group_by: GroupBy,
+    /// Keys of the sections whose projects are hidden, see `GroupBy::key`.
+    collapsed_groups: Vec<String>,
+    /// Projects shown expanded although they're collapsed in the DB, which can't be changed
+    /// in read-only mode.
+    expanded: Vec<String>,
+    search: String,
 }
 
-fn render_projects<'a>(project_list_state: &ListState) -> (List<'a>, Option<Table<'a>>) {
-    let projects = Block::default()
-        .borders(Borders::ALL)
-        .style(Style::default().fg(Color::White))
-        .title("Projects")
-        .border_type(BorderType::Plain);
-
-    let project_list = read_db().expect("can fetch project list");
+/// How values in the detail pane are shown, toggled from the keyboard.
+#[derive(Clone, Copy, Debug, Default)]
+struct DisplayOptions {
+    /// Exact timestamps instead of "3 days ago".
+    exact_times: bool,
+    /// Whole directories, wrapped over several lines, instead of shortened ones.
+    full_paths: bool,
+    /// The detail as one label/value row per field instead of a table with a column per field.
+    vertical_detail: bool,
+    /// Language badges as Nerd Font icons, from the config.
+    nerd_font: bool,
+}
 
-    let items: Vec<_> = project_list
-        .iter()
-        .map(|project| {
-            ListItem::new(Spans::from(vec![Span::styled(
-                    project.name.clone(),
-                Style::default(),
-            )]))
-        })
-        .collect();
+/// Every workspace in use with its number of projects, sorted by name.
+fn workspaces(projects: &[Project]) -> Vec<(String, usize)> {
+    let mut workspaces: Vec<(String, usize)> = Vec::new();
+    for workspace in projects.iter().filter_map(|p| p.workspace.as_ref()) {
+        match workspaces.iter_mut().find(|(name, _)| name == workspace) {
+            Some((_, count)) => *count += 1,
+            None => workspaces.push((workspace.clone(), 1)),
+        }
+    }
+    workspaces.sort();
+    workspaces
+}
 
-    let list = List::new(items).block(projects).highlight_style(
-            Style::default()
-            .bg(Color::Yellow)
-            .fg(Color::Black)
-            .add_modifier(Modifier::BOLD),
-    );
+/// Cycles the list filter: no filter, then every status in turn.
+fn next_status_filter(filter: Option<Status>) -> Option<Status> {
+    match filter {
+        None => Some(Status::ALL[0]),
+        Some(status) if status == Status::ALL[Status::ALL.len() - 1] => None,
+        Some(status) => Some(status.next()),
+    }
+}
 
-    // Display selected project if there's any selected
-    let selected_project_id = project_list_state.selected();
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum PromptKind {
+    Alias,
+    Jump,
+    Parent,
+    DueDate,
+    Todo,
+    Link(LinkKind),
+    Search,
+    Tags,
+    Workspace,
+}
 
-    if selected_project_id == None || project_list.len() == 0 {
-        let project_detail = Some(Table::new(vec![]).block(
-                Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White))
-                .title("No project selected")
-                .border_type(BorderType::Plain),
-        ));
+/// Every action of the command palette, run by pressing the key it's bound to.
+const COMMANDS: [(&str, KeyCode, KeyModifiers); 49] = [
+    ("Add a project", KeyCode::Char('a'), KeyModifiers::NONE),
+    ("Edit the selected project", KeyCode::Char('e'), KeyModifiers::NONE),
+    ("Rename the selected project", KeyCode::Char('r'), KeyModifiers::NONE),
+    ("Delete the selected or marked projects", KeyCode::Char('d'), KeyModifiers::NONE),
+    ("Undo the last delete", KeyCode::Char('u'), KeyModifiers::NONE),
+    ("Archive the selected or marked projects", KeyCode::Char('z'), KeyModifiers::NONE),
+    ("Tag the selected or marked projects", KeyCode::Char('#'), KeyModifiers::NONE),
+    ("Move the selected or marked projects to a workspace", KeyCode::Char('W'), KeyModifiers::NONE),
+    ("Cycle the status", KeyCode::Char('c'), KeyModifiers::NONE),
+    ("Filter by status", KeyCode::Char('f'), KeyModifiers::NONE),
+    ("Raise the priority", KeyCode::Char('+'), KeyModifiers::NONE),
+    ("Lower the priority", KeyCode::Char('-'), KeyModifiers::NONE),
+    ("Sort the list", KeyCode::Char('s'), KeyModifiers::NONE),
+    ("Group the list by tag, workspace, directory or nothing", KeyCode::Char('S'), KeyModifiers::NONE),
+    ("Search", KeyCode::Char('/'), KeyModifiers::NONE),
+    ("Jump to a project by alias or name", KeyCode::Char('g'), KeyModifiers::NONE),
+    ("Set an alias", KeyCode::Char('A'), KeyModifiers::NONE),
+    ("Set the parent project", KeyCode::Char('P'), KeyModifiers::NONE),
+    ("Set a due date", KeyCode::Char('D'), KeyModifiers::NONE),
+    ("Start or stop the timer", KeyCode::Char('T'), KeyModifiers::NONE),
+    ("Add a TODO", KeyCode::Char('t'), KeyModifiers::NONE),
+    ("Link a related project", KeyCode::Char('l'), KeyModifiers::NONE),
+    ("Link a project this one depends on", KeyCode::Char('L'), KeyModifiers::NONE),
+    ("Open the selected project in the editor", KeyCode::Enter, KeyModifiers::NONE),
+    ("Switch to the tmux session of the selected project", KeyCode::Char('m'), KeyModifiers::NONE),
+    ("Start a shell in the directory of the selected project", KeyCode::Char('!'), KeyModifiers::NONE),
+    ("Open the repository in the browser", KeyCode::Char('o'), KeyModifiers::NONE),
+    ("Open the directory with an app or in the file manager", KeyCode::Char('O'), KeyModifiers::NONE),
+    ("Copy the directory", KeyCode::Char('y'), KeyModifiers::NONE),
+    ("Copy the name", KeyCode::Char('Y'), KeyModifiers::NONE),
+    ("Show or hide the README", KeyCode::Char('R'), KeyModifiers::NONE),
+    ("Check all repositories for changes", KeyCode::Char('G'), KeyModifiers::NONE),
+    ("Discover new projects in the project roots", KeyCode::Char('N'), KeyModifiers::NONE),
+    ("Measure the disk usage of all projects", KeyCode::Char('U'), KeyModifiers::NONE),
+    ("Add a project by picking its directory", KeyCode::Char('B'), KeyModifiers::NONE),
+    ("Add a project with xplr", KeyCode::Char('X'), KeyModifiers::NONE),
+    ("Show the home screen", KeyCode::Char('h'), KeyModifiers::NONE),
+    ("Show all projects", KeyCode::Char('p'), KeyModifiers::NONE),
+    ("Show the board", KeyCode::Char('b'), KeyModifiers::NONE),
+    ("Show the week", KeyCode::Char('w'), KeyModifiers::NONE),
+    ("Show the timeline", KeyCode::Char('i'), KeyModifiers::NONE),
+    ("Show the git status of all repositories", KeyCode::Char('v'), KeyModifiers::NONE),
+    ("Switch to the next theme", KeyCode::Char('C'), KeyModifiers::NONE),
+    ("Toggle exact timestamps", KeyCode::Char('E'), KeyModifiers::NONE),
+    ("Toggle full directories", KeyCode::Char('F'), KeyModifiers::NONE),
+    ("Toggle the vertical detail layout", KeyCode::Char('V'), KeyModifiers::NONE),
+    ("Show background tasks", KeyCode::Char('t'), KeyModifiers::CONTROL),
+    ("Show the keys", KeyCode::Char('?'), KeyModifiers::NONE),
+    ("Quit", KeyCode::Char('q'), KeyModifiers::NONE),
+];
 
-        (list, project_detail)
+/// How a key is written in the UI, like "Ctrl+T".
+fn key_label(code: KeyCode, modifiers: KeyModifiers) -> String {
+    let key = match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => c.to_ascii_uppercase().to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        other => format!("{:?}", other),
+    };
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        format!("Ctrl+{}", key)
     } else {
-        let selected_project = project_list
-            .get(selected_project_id.expect("there is a selected project"))
-            .unwrap()
-            .clone();
-
-        let project_detail = Table::new(vec![Row::new(vec![
-            Cell::from(Span::raw(selected_project.id.to_string())),
-            Cell::from(Span::raw(selected_project.name)),
-            Cell::from(Span::raw(selected_project.directory)),
-            Cell::from(Span::raw(selected_project.created_at.to_string())),
-        ])])
-        .header(Row::new(vec![
-            Cell::from(Span::styled(
-                    "ID",
-                Style::default().add_modifier(Modifier::BOLD),
-            )),
-            Cell::from(Span::styled(
-                    "Name",
-                Style::default().add_modifier(Modifier::BOLD),
-            )),
-            Cell::from(Span::styled(
-                    "Directory",
-                Style::default().add_modifier(Modifier::BOLD),
-            )),
-            Cell::from(Span::styled(
-                    "Created At",
-                Style::default().add_modifier(Modifier::BOLD),
-            )),
-        ]))
-        .block(
-                Block::default()
-                .borders(Borders::ALL)
-                .style(Style::default().fg(Color::White))
-                .title("Detail")
-                .border_type(BorderType::Plain),
-        )
-        .widths(&[
-            Constraint::Percentage(25),
-            Constraint::Percentage(15),
-            Constraint::Percentage(50),
-            Constraint::Percentage(20),
-            ]);
+        key
+    }
+}
 
-        (list, Some(project_detail))
+/// The Ctrl+P popup for finding an action by name.
+#[derive(Default)]
+struct CommandPalette {
+    query: String,
+    state: ListState,
+}
+
+impl CommandPalette {
+    /// The commands matching the query, best match first.
+    fn matches(&self) -> Vec<(&'static str, KeyCode, KeyModifiers)> {
+        if self.query.is_empty() {
+            return COMMANDS.to_vec();
+        }
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<_> = COMMANDS
+            .iter()
+            .filter_map(|command| matcher.fuzzy_match(command.0, &self.query).map(|score| (score, *command)))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        scored.into_iter().map(|(_, command)| command).collect()
     }
 }
 
-fn get_db_path() -> Arc<String> {
-    let home_dir = home_dir().unwrap();
-    let db_path: String = home_dir.to_str().unwrap().to_string() + DB_PATH;
-    let db_file = db_path.to_owned() + "/db.json";
+/// A single-line text input shown in the bottom bar.
+struct Prompt {
+    kind: PromptKind,
+    input: String,
+    error: Option<String>,
+}
 
-    fs::create_dir_all(db_path);
-    if !Path::new(db_file.as_str()).exists() {
-        let mut file = File::create(db_file.as_str()).expect("DB file created");
-        file.write_all(b"[]");
+impl Prompt {
+    fn new(kind: PromptKind, input: String) -> Prompt {
+        Prompt {
+            kind,
+            input,
+            error: None,
+        }
     }
 
-    let arc = Arc::new(db_file);
+    fn label(&self) -> &'static str {
+        match self.kind {
+            PromptKind::Alias => "Alias (empty to clear)",
+            PromptKind::Jump => "Jump to",
+            PromptKind::Parent => "Parent project (empty for none)",
+            PromptKind::DueDate => "Due date, YYYY-MM-DD (empty to clear)",
+            PromptKind::Todo => "New TODO",
+            PromptKind::Search => "Search",
+            PromptKind::Tags => "Tags to add, '-tag' removes",
+            PromptKind::Workspace => "Move to workspace (empty for none)",
+            PromptKind::Link(LinkKind::DependsOn) => "Depends on (an existing link is removed)",
+            PromptKind::Link(LinkKind::RelatedTo) => "Related to (an existing link is removed)",
+        }
+    }
+}
 
-    arc.clone()
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ToastKind {
+    Success,
+    Warning,
+    Error,
 }
 
-fn read_db() -> Result<Vec<Project>, Error> {
-    let db_content = fs::read_to_string(get_db_path().to_string())?;
-    let parsed: Vec<Project> = serde_json::from_str(&db_content)?;
-    Ok(parsed)
+/// A short-lived notification shown in the corner of the screen.
+struct Toast {
+    text: String,
+    kind: ToastKind,
+    shown_at: Instant,
 }
 
-fn add_project_to_db(project_name: String, directory: String) -> Result<Vec<Project>, Error> {
-    let db_content = fs::read_to_string(get_db_path().to_string())?;
-    let mut parsed: Vec<Project> = serde_json::from_str(&db_content)?;
+impl Toast {
+    fn new(kind: ToastKind, text: String) -> Toast {
+        Toast {
+            text,
+            kind,
+            shown_at: Instant::now(),
+        }
+    }
 
-    let new_project = Project {
-        id: Uuid::new_v4().to_string(),
-        name: project_name,
-        directory: directory,
-        created_at: Utc::now(),
-    };
+    /// Errors stay up longer, they're more likely to need reading twice.
+    fn is_expired(&self) -> bool {
+        let timeout = match self.kind {
+            ToastKind::Error => Duration::from_secs(8),
+            ToastKind::Success | ToastKind::Warning => Duration::from_secs(4),
+        };
+        self.shown_at.elapsed() >= timeout
+    }
+}
 
-    parsed.push(new_project);
-    fs::write(get_db_path().to_string(), &serde_json::to_vec(&parsed)?)?;
-    Ok(parsed)
+/// The built-in form for adding a project.
+#[derive(Default)]
+struct AddForm {
+    directory: String,
+    name: String,
+    /// 0 for the directory field, 1 for the name field.
+    field: usize,
+    error: Option<String>,
 }
 
-fn remove_project_at_index(project_list_state: &mut ListState) -> Result<(), Error> {
-    if let Some(selected) = project_list_state.selected() {
-        let db_content = fs::read_to_string(get_db_path().to_string())?;
-        let mut parsed: Vec<Project> = serde_json::from_str(&db_content)?;
-        parsed.remove(selected);
-        fs::write(get_db_path().to_string(), &serde_json::to_vec(&parsed)?)?;
-        // let amount_projects = read_db().expect("can fetch project list").len();
-        if selected > 0 {
-            project_list_state.select(Some(selected - 1));
+impl AddForm {
+    fn input(&mut self) -> &mut String {
+        if self.field == 0 {
+            &mut self.directory
         } else {
-            project_list_state.select(Some(0));
+            &mut self.name
+        }
+    }
+
+    /// Validates the form and adds the project, naming it after its directory unless a name was given.
+    /// Returns the name of the new project.
+    fn submit(&self) -> Result<String, Error> {
+        let directory = expand_tilde(self.directory.trim());
+        if !directory.is_dir() {
+            return Err(Error::NotADirectory(self.directory.trim().to_string()));
+        }
+        let directory = directory.canonicalize()?;
+        let name = match self.name.trim() {
+            "" => directory
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| directory.to_string_lossy().to_string()),
+            name => name.to_string(),
+        };
+
+        add_project_to_db(name.clone(), directory.to_string_lossy().to_string())?;
+        Ok(name)
+    }
+}
+
+/// The form for editing the selected project.
+struct EditForm {
+    id: String,
+    name: String,
+    directory: String,
+    /// Space-separated.
+    tags: String,
+    notes: String,
+    open_command: String,
+    status: Status,
+    /// Index into `EditForm::FIELDS`.
+    field: usize,
+    error: Option<String>,
+}
+
+impl EditForm {
+    const FIELDS: [&'static str; 6] = ["Name", "Directory", "Tags", "Notes", "Open with", "Status"];
+
+    fn new(project: &Project) -> EditForm {
+        EditForm {
+            id: project.id.clone(),
+            name: project.name.clone(),
+            directory: project.directory.clone(),
+            tags: project.tags.join(" "),
+            notes: project.notes.clone(),
+            open_command: project.open_command.clone().unwrap_or_default(),
+            status: project.status,
+            field: 0,
+            error: None,
+        }
+    }
+
+    /// The text of the active field, `None` for the status, which isn't typed in.
+    fn input(&mut self) -> Option<&mut String> {
+        match self.field {
+            0 => Some(&mut self.name),
+            1 => Some(&mut self.directory),
+            2 => Some(&mut self.tags),
+            3 => Some(&mut self.notes),
+            4 => Some(&mut self.open_command),
+            _ => None,
+        }
+    }
+
+    fn next_field(&mut self) {
+        self.field = (self.field + 1) % EditForm::FIELDS.len();
+    }
+
+    fn previous_field(&mut self) {
+        self.field = (self.field + EditForm::FIELDS.len() - 1) % EditForm::FIELDS.len();
+    }
+
+    /// Validates the form and saves it to the project.
+    fn submit(&self) -> Result<(), Error> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(Error::EmptyName);
+        }
+        let directory = expand_tilde(self.directory.trim());
+        if !directory.is_dir() {
+            return Err(Error::NotADirectory(self.directory.trim().to_string()));
+        }
+        let directory = directory.canonicalize()?.to_string_lossy().to_string();
+        let mut tags: Vec<String> = Vec::new();
+        for tag in self.tags.split_whitespace().map(|tag| tag.trim_start_matches('#')) {
+            if !tag.is_empty() && !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+            }
+        }
+
+        update_projects(std::slice::from_ref(&self.id), |p| {
+            p.name = name.to_string();
+            p.directory = directory.clone();
+            p.tags = tags.clone();
+            p.notes = self.notes.trim().to_string();
+            p.open_command = Some(self.open_command.trim().to_string()).filter(|command| !command.is_empty());
+            p.status = self.status;
+        })
+    }
+}
+
+/// Renaming the selected project in place in the list.
+struct InlineRename {
+    id: String,
+    input: String,
+    /// Position of the cursor, in characters.
+    cursor: usize,
+}
+
+impl InlineRename {
+    fn new(project: &Project) -> InlineRename {
+        InlineRename {
+            id: project.id.clone(),
+            input: project.name.clone(),
+            cursor: project.name.chars().count(),
+        }
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.input.char_indices().nth(cursor).map_or(self.input.len(), |(i, _)| i)
+    }
+
+    fn insert(&mut self, c: char) {
+        let index = self.byte_index(self.cursor);
+        self.input.insert(index, c);
+        self.cursor += 1;
+    }
+
+    fn backspace(&mut self) {
+        if self.cursor > 0 {
+            self.cursor -= 1;
+            let index = self.byte_index(self.cursor);
+            self.input.remove(index);
+        }
+    }
+
+    fn delete(&mut self) {
+        if self.cursor < self.input.chars().count() {
+            let index = self.byte_index(self.cursor);
+            self.input.remove(index);
+        }
+    }
+
+    fn move_cursor(&mut self, offset: isize) {
+        self.cursor = self.cursor.saturating_add_signed(offset).min(self.input.chars().count());
+    }
+
+    /// Saves the new name and returns it.
+    fn submit(&self) -> Result<String, Error> {
+        let name = self.input.trim();
+        if name.is_empty() {
+            return Err(Error::EmptyName);
         }
+        update_projects(std::slice::from_ref(&self.id), |p| p.name = name.to_string())?;
+        Ok(name.to_string())
+    }
+}
+
+/// Which pane of the Projects tab receives navigation keys.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Focus {
+    Projects,
+    Todos,
+    Links,
+    Remotes,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq, Eq, Default)]
+enum MenuItem {
+    #[default]
+    Home,
+    Projects,
+    Board,
+    Week,
+    Timeline,
+    Git,
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        cli::run(&args);
+        return Ok(());
     }
+
+    run_ui(false, None)?;
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Runs the interactive UI until it's quit. As a picker, it returns the chosen project.
+fn run_ui(picker: bool, pick_sort: Option<SortMode>) -> Result<Option<Project>, Box<dyn std::error::Error>> {
+    let config = config::load()?;
+    let theme = Theme::load(&config.theme, &config.colors)?;
+    // Report a broken DB before taking over the terminal.
+    let projects = read_db()?;
+    if config.notifications && !picker {
+        notify::remind_due(&projects);
+    }
+
+    // The picker's output is captured by the shell, so it draws on stderr instead.
+    let mut output: Box<dyn Write> = if picker { Box::new(io::stderr()) } else { Box::new(io::stdout()) };
+    enable_raw_mode().expect("can run in raw mode");
+    if config.mouse {
+        execute!(output, EnableMouseCapture)?;
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let tick_rate = Duration::from_millis(200);
+    let paused = Arc::new(AtomicBool::new(false));
+    let input_paused = paused.clone();
+    thread::spawn(move || {
+        let mut last_tick = Instant::now();
+        let mut reported_pause = false;
+        loop {
+            // Leaves the terminal to the editor while it runs.
+            if input_paused.load(Ordering::Relaxed) {
+                if !reported_pause && tx.send(Event::Paused).is_err() {
+                    break;
+                }
+                reported_pause = true;
+                thread::sleep(Duration::from_millis(20));
+                continue;
+            }
+            reported_pause = false;
+
+            let timeout = tick_rate
+                .checked_sub(last_tick.elapsed())
+                .unwrap_or_else(|| Duration::from_secs(0));
+
+            if event::poll(timeout).expect("poll works") {
+                match event::read().expect("can read events") {
+                    CEvent::Key(key) => tx.send(Event::Input(key)).expect("can send events"),
+                    CEvent::Mouse(mouse) => tx.send(Event::Mouse(mouse)).expect("can send events"),
+                    CEvent::Resize(..) => {}
+                }
+            }
+
+            if last_tick.elapsed() >= tick_rate && tx.send(Event::Tick).is_ok() {
+                last_tick = Instant::now();
+            }
+        }
+    });
+
+    let backend = CrosstermBackend::new(output);
+    let mut terminal = Terminal::new(backend)?;
+    terminal.clear()?;
+
+    let mut app = App::new(config.clone(), theme);
+    if picker {
+        app.start_picker(pick_sort);
+    } else if !FRESH.load(Ordering::Relaxed) {
+        app.restore_session()?;
+    }
+    while !app.quit {
+        terminal.draw(|rect| {
+            app.draw(rect);
+            if config.ascii {
+                rect.render_widget(ascii::AsciiOnly, rect.size());
+            }
+        })?;
+
+        match rx.recv()? {
+            Event::Input(event) => app.handle_key(event),
+            Event::Mouse(mouse) => app.handle_mouse(mouse),
+            Event::Tick => app.tick(),
+            Event::Paused => {}
+        }
+
+        if let Some(foreground) = app.foreground.take() {
+            paused.store(true, Ordering::Relaxed);
+            while !matches!(rx.recv()?, Event::Paused) {}
+            match foreground {
+                Foreground::Command(project, mut command) => {
+                    let result = run_in_foreground(&mut terminal, &config, || run_to_end(&mut command));
+                    app.foreground_finished(&project, result);
+                }
+                Foreground::Shell(project, mut command) => {
+                    let result = run_in_foreground(&mut terminal, &config, || {
+                        command.status().map(|_| ()).map_err(|err| format!("can't run a shell: {}", err))
+                    });
+                    app.foreground_finished(&project, result);
+                }
+                Foreground::Picker(picker, start) => {
+                    let result = run_in_foreground(&mut terminal, &config, || picker.pick(&start));
+                    app.directory_picked(picker.name(), result);
+                }
+            }
+            paused.store(false, Ordering::Relaxed);
+        }
+    }
+
+    disable_raw_mode()?;
+    if config.mouse {
+        execute!(terminal.backend_mut(), DisableMouseCapture)?;
+    }
+    // The picker runs in the middle of a shell session, like from a key binding, which
+    // goes on where it was.
+    if picker {
+        terminal.clear()?;
+    }
+    terminal.show_cursor()?;
+    Ok(app.picked)
+}
+
+/// Hands the terminal to `run`, like the editor, until it's done, then takes it back
+/// and redraws. The input thread has to be paused.
+fn run_in_foreground<T>(
+    terminal: &mut Terminal<CrosstermBackend<Box<dyn Write>>>,
+    config: &config::Config,
+    run: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    disable_raw_mode().map_err(|err| err.to_string())?;
+    if config.mouse {
+        let _ = execute!(terminal.backend_mut(), DisableMouseCapture);
+    }
+    let _ = terminal.clear();
+    let _ = terminal.show_cursor();
+
+    let result = run();
+
+    enable_raw_mode().map_err(|err| err.to_string())?;
+    if config.mouse {
+        let _ = execute!(terminal.backend_mut(), EnableMouseCapture);
+    }
+    let _ = terminal.clear();
+    result
+}
+
+/// Runs `command` until it exits. The error says how it failed.
+fn run_to_end(command: &mut Command) -> Result<(), String> {
+    match command.status() {
+        Ok(status) if status.success() => Ok(()),
+        Ok(status) => Err(format!("{} exited with {}", command.get_program().to_string_lossy(), status)),
+        Err(err) => Err(format!("can't run {}: {}", command.get_program().to_string_lossy(), err)),
+    }
+}
+
+/// The command that opens the project: its own open command, or the editor of the
+/// config, or `$VISUAL` / `$EDITOR`. `None` if the command is empty.
+fn open_command(config: &config::Config, project: &Project) -> Option<Command> {
+    let editor = match (&project.open_command, &config.editor) {
+        (Some(command), _) if !command.trim().is_empty() => command.clone(),
+        (_, Some(editor)) => editor.clone(),
+        _ => std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| "vi".to_string()),
+    };
+    command_for_directory(&editor, &project.directory)
+}
+
+/// The command line `template` as a command, with `directory` where `{}` or `{dir}` is,
+/// or at the end. `None` if the command line is empty.
+fn command_for_directory(template: &str, directory: &str) -> Option<Command> {
+    let mut words: Vec<String> = template.split_whitespace().map(str::to_string).collect();
+    if words.is_empty() {
+        return None;
+    }
+    if words.iter().any(|word| word.contains("{}") || word.contains("{dir}")) {
+        for word in &mut words {
+            *word = word.replace("{dir}", directory).replace("{}", directory);
+        }
+    } else {
+        words.push(directory.to_string());
+    }
+    let mut command = Command::new(&words[0]);
+    command.args(&words[1..]);
+    Some(command)
+}
+
+/// Runs `script` with `sh -c`, or `cmd /C` on Windows.
+fn script_command(script: &str) -> Command {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(script);
+    command
+}
+
+/// The user's shell, `$SHELL` or `%COMSPEC%` on Windows, started in the directory of the
+/// project with its environment variables.
+fn shell_command(project: &Project) -> Command {
+    let shell = if cfg!(target_os = "windows") {
+        std::env::var("COMSPEC").unwrap_or_else(|_| "cmd".to_string())
+    } else {
+        std::env::var("SHELL").unwrap_or_else(|_| "sh".to_string())
+    };
+    let mut command = Command::new(shell);
+    command.current_dir(&project.directory).envs(&project.env);
+    command
+}
+
+/// Overall numbers for the dashboard: how many projects there are, by status.
+fn render_overview<'a>(projects: &[Project], theme: &Theme) -> Paragraph<'a> {
+    let mut spans = vec![
+        Span::styled(format!("{} projects", projects.len()), Style::default().add_modifier(Modifier::BOLD)),
+    ];
+    for status in Status::ALL {
+        let count = projects.iter().filter(|p| p.status == status).count();
+        spans.push(Span::raw("   "));
+        spans.push(Span::styled(format!("{} {}", count, status.label()), Style::default().fg(theme.status(status))));
+    }
+    let overdue = projects.iter().filter(|p| p.is_overdue()).count();
+    if overdue > 0 {
+        spans.push(Span::styled(format!("   {} overdue", overdue), theme.error()));
+    }
+
+    Paragraph::new(Spans::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(false))
+            .title(vec![Span::styled("whisk-CLI", Style::default().fg(theme.title)), Span::styled(" (? for help)", theme.muted())])
+            .border_type(BorderType::Plain),
+    )
+}
+
+/// A titled list for one of the dashboard panels.
+fn render_dashboard_list<'a>(title: &str, items: Vec<ListItem<'a>>, theme: &Theme) -> List<'a> {
+    let items = if items.is_empty() {
+        vec![ListItem::new(Span::styled("Nothing here", theme.muted()))]
+    } else {
+        items
+    };
+
+    List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(false))
+            .title(title.to_string())
+            .border_type(BorderType::Plain),
+    )
+}
+
+/// Number of projects per tag, most used first.
+fn tag_counts(projects: &[Project]) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for tag in projects.iter().flat_map(|p| p.tags.iter()) {
+        match counts.iter_mut().find(|(name, _)| name == tag) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((tag.clone(), 1)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// How many recently opened projects Home lists, each reachable with its number key.
+const RECENT_PROJECTS: usize = 5;
+
+/// The projects opened last, most recent first.
+fn recently_opened(projects: &[Project]) -> Vec<&Project> {
+    let mut opened: Vec<&Project> = projects.iter().filter(|p| p.last_opened_at.is_some()).collect();
+    opened.sort_by_key(|p| std::cmp::Reverse(p.last_opened_at));
+    opened.truncate(RECENT_PROJECTS);
+    opened
+}
+
+fn render_help<'a>(theme: &Theme) -> Paragraph<'a> {
+    let help = Paragraph::new(vec![
+        Spans::from(vec![Span::raw("Use Up/Down, PageUp/PageDown and Home/End to move through the list.")]),
+        Spans::from(vec![Span::raw("Press 'p' to access projects, 'a' to add a new project and 'd' to delete the currently selected project ('u' undoes it).")]),
+        Spans::from(vec![Span::raw("On Home, press 1-5 to jump to one of the recently opened projects.")]),
+        Spans::from(vec![Span::raw("Press Ctrl+P to find any action by name.")]),
+        Spans::from(vec![Span::raw("Press 1-9 to select the Nth project on screen and Alt+letter for the next project starting with it.")]),
+        Spans::from(vec![Span::raw("Press 'B' to pick the directory of a new project with the picker of the config, or 'X' to use xplr.")]),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::raw("Press 'b' for the board, where Tab switches columns and Left/Right move a project to another status.")]),
+        Spans::from(vec![Span::raw("Press 'r' or F2 to rename the selected project in the list; Enter saves and Esc cancels.")]),
+        Spans::from(vec![Span::raw("Press 'e' to edit the name, directory, tags, notes and status of the selected project.")]),
+        Spans::from(vec![Span::raw("Press 'c' to cycle the status of the selected project and 'f' to filter the list by status.")]),
+        Spans::from(vec![Span::raw("Use '+'/'-' to change its priority and 's' to choose how the list is sorted.")]),
+        Spans::from(vec![Span::raw("Press 'S' to group the list by tag, workspace or directory; Space or Enter on a header collapses it until expanded again.")]),
+        Spans::from(vec![Span::raw("Press 'E' to switch between relative and exact timestamps and 'F' to show full directories.")]),
+        Spans::from(vec![Span::raw("Press 'C' to switch to the next color theme.")]),
+        Spans::from(vec![Span::raw("Press 'V' to show the detail as a list of fields instead of a table.")]),
+        Spans::from(vec![Span::raw("Press Enter to open the selected project with its \"Open with\" command from 'e', or the editor of the config, or $VISUAL / $EDITOR.")]),
+        Spans::from(vec![Span::raw("Press 'm' for a tmux session named after the selected project, started in its directory if there's none.")]),
+        Spans::from(vec![Span::raw("Press '!' for a shell in the directory of the selected project, with its environment variables; exit it to return.")]),
+        Spans::from(vec![Span::raw("Press 'o' to open the repository of the selected project in your browser and 'O' its directory in the file manager.")]),
+        Spans::from(vec![Span::raw("With launchers in the config, 'O' offers those apps first, like VS Code or lazygit.")]),
+        Spans::from(vec![Span::raw("Press 'y' to copy the directory of the selected project to the clipboard and 'Y' its name.")]),
+        Spans::from(vec![Span::raw("Press 'A' to give the selected project an alias and 'g' to jump to a project by alias or name.")]),
+        Spans::from(vec![Span::raw("Press 'P' to nest the selected project under a parent and Left/Right to collapse or expand sub-projects.")]),
+        Spans::from(vec![Span::raw("Use Shift+Up/Down (or 'K'/'J') to reorder projects; this switches to the manual sort.")]),
+        Spans::from(vec![Span::raw("Press 'D' to set a due date. Overdue projects are shown in red.")]),
+        Spans::from(vec![Span::raw("Press 't' to add a TODO and Tab to focus the TODO list, where Space toggles and 'd' removes an item.")]),
+        Spans::from(vec![Span::raw("Tab again focuses the links and then the git remotes, where Enter opens and 'y' copies the URL.")]),
+        Spans::from(vec![Span::raw("Press 'T' to start or stop the timer of the selected project and 'w' for this week's time summary.")]),
+        Spans::from(vec![Span::raw("Press 'i' for a timeline of when projects were created and last worked on.")]),
+        Spans::from(vec![Span::raw("Press 'l' to link a related project and 'L' to add a dependency. Tab twice focuses the links, Enter jumps across.")]),
+        Spans::from(vec![Span::raw("Press 'R' to show the README of the selected project instead of its TODOs, links and files.")]),
+        Spans::from(vec![Span::raw("Projects moved to a workspace with 'W' get their own tab, Alt+1 to Alt+9 switch to them.")]),
+        Spans::from(vec![Span::raw("Press 'G' to check all repositories for uncommitted changes in the background, Ctrl+T to list running tasks.")]),
+        Spans::from(vec![Span::raw("Press 'N' to look for new projects in the project_roots of the config and choose which to add.")]),
+        Spans::from(vec![Span::raw("Press 'v' for the branch, changed files and unpushed commits of every repository.")]),
+        Spans::from(vec![Span::raw("A ⚠ after the name means the directory is gone; `whisk prune` removes or archives those projects.")]),
+        Spans::from(vec![Span::raw("Sizes of project directories are measured when selected, or all at once with 'U'. Sort by size with 's'.")]),
+        Spans::from(vec![Span::raw("Press '/' to search projects by name, path or tag. Esc clears the search.")]),
+        Spans::from(vec![Span::raw("Space marks projects for 'd' delete, 'z' archive, '#' tag and 'W' move to a workspace. Esc unmarks.")]),
+    ])
+    .wrap(Wrap { trim: true })
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(true))
+            .title("Keys (Esc closes)")
+            .border_type(BorderType::Plain),
+    );
+    help
+}
+
+fn render_add_form<'a>(form: &AddForm, theme: &Theme) -> Paragraph<'a> {
+    let field = |label: &str, value: &str, active: bool| {
+        let label_style = if active {
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+        } else {
+            theme.text()
+        };
+        let mut spans = vec![
+            Span::styled(format!("{:<11}", label), label_style),
+            Span::raw(value.to_string()),
+        ];
+        if active {
+            spans.push(Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)));
+        }
+        Spans::from(spans)
+    };
+
+    let mut lines = vec![
+        field("Directory", &form.directory, form.field == 0),
+        field("Name", &form.name, form.field == 1),
+        Spans::from(vec![Span::raw("")]),
+        Spans::from(vec![Span::styled(
+            "Tab switches fields, Ctrl+B browses, Enter adds, Esc cancels. An empty name uses the directory name.",
+            theme.muted(),
+        )]),
+    ];
+    if let Some(error) = &form.error {
+        lines.push(Spans::from(vec![Span::styled(error.clone(), theme.error())]));
+    }
+
+    Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+                .border_style(theme.border(false))
+            .title("Add project")
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn render_edit_form<'a>(form: &EditForm, theme: &Theme) -> Paragraph<'a> {
+    let mut lines: Vec<Spans> = EditForm::FIELDS
+        .iter()
+        .enumerate()
+        .map(|(i, label)| {
+            let active = form.field == i;
+            let label_style = if active {
+                Style::default().fg(theme.accent).add_modifier(Modifier::BOLD)
+            } else {
+                theme.text()
+            };
+            let value = match i {
+                0 => Span::raw(form.name.clone()),
+                1 => Span::raw(form.directory.clone()),
+                2 => Span::raw(form.tags.clone()),
+                3 => Span::raw(form.notes.clone()),
+                4 if form.open_command.is_empty() && !active => Span::styled("the editor", theme.muted()),
+                4 => Span::raw(form.open_command.clone()),
+                _ => Span::styled(
+                    format!("< {} >", form.status.label()),
+                    Style::default().fg(theme.status(form.status)),
+                ),
+            };
+            let mut spans = vec![Span::styled(format!("{:<11}", label), label_style), value];
+            if active && i < 5 {
+                spans.push(Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)));
+            }
+            Spans::from(spans)
+        })
+        .collect();
+    lines.push(Spans::from(vec![Span::raw("")]));
+    lines.push(Spans::from(vec![Span::styled(
+        "Tab/Up/Down switch fields, Left/Right change the status, Enter saves, Esc cancels.",
+        theme.muted(),
+    )]));
+    if let Some(error) = &form.error {
+        lines.push(Spans::from(vec![Span::styled(error.clone(), theme.error())]));
+    }
+
+    Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(true))
+            .title("Edit project")
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn render_notes<'a>(notes: &str, theme: &Theme) -> Paragraph<'a> {
+    Paragraph::new(notes.to_string()).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(false))
+            .title("Notes")
+            .border_type(BorderType::Plain),
+    )
+}
+
+/// A rectangle `percent_x`% wide and `height` rows high, centered in `area`.
+fn centered_rect(percent_x: u16, height: u16, area: Rect) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+fn render_prompt<'a>(prompt: &Prompt, theme: &Theme) -> Paragraph<'a> {
+    let mut spans = vec![
+        Span::styled(
+            format!("{}: ", prompt.label()),
+            Style::default().fg(theme.accent),
+        ),
+        Span::raw(prompt.input.clone()),
+        Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+    ];
+    if let Some(error) = &prompt.error {
+        spans.push(Span::styled(
+            format!("  {}", error),
+            theme.error(),
+        ));
+    }
+
+    Paragraph::new(Spans::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+                .border_style(theme.border(false))
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn render_command_palette<'a>(palette: &CommandPalette, theme: &Theme) -> List<'a> {
+    let items: Vec<_> = palette
+        .matches()
+        .into_iter()
+        .map(|(label, code, modifiers)| {
+            ListItem::new(Spans::from(vec![
+                Span::raw(label),
+                Span::styled(format!("  {}", key_label(code, modifiers)), theme.muted()),
+            ]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(true))
+                .title(format!("Command: {}", palette.query))
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(theme.selection())
+}
+
+fn render_sort_menu<'a>(current: SortMode, theme: &Theme) -> List<'a> {
+    let items: Vec<_> = SortMode::ALL
+        .iter()
+        .map(|mode| {
+            let marker = if *mode == current { "● " } else { "  " };
+            ListItem::new(Spans::from(vec![Span::raw(format!("{}{}", marker, mode.label()))]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(true))
+                .title("Sort by")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(theme.selection())
+}
+
+/// The 'O' menu: the launchers of the config, then the file manager.
+fn render_open_with_menu<'a>(launchers: &[config::Launcher], theme: &Theme) -> List<'a> {
+    let items: Vec<_> = launchers
+        .iter()
+        .map(|launcher| launcher.name.clone())
+        .chain(std::iter::once("File manager".to_string()))
+        .map(|name| ListItem::new(Spans::from(vec![Span::raw(name)])))
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(true))
+                .title("Open with")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(theme.selection())
+}
+
+/// The bottom bar listing the keys of the current view, like "a add • d delete • ? help".
+fn render_key_hints<'a>(hints: &[(&'a str, &'a str)], theme: &Theme) -> Paragraph<'a> {
+    let mut spans = Vec::new();
+    for (i, (key, action)) in hints.iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" • ", theme.muted()));
+        }
+        spans.push(Span::styled(*key, Style::default().fg(theme.accent)));
+        spans.push(Span::raw(format!(" {}", action)));
+    }
+
+    Paragraph::new(Spans::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(false))
+            .border_type(BorderType::Plain),
+    )
+}
+
+/// The bottom bar while background tasks are running.
+fn render_task_status<'a>(runner: &tasks::TaskRunner, theme: &Theme) -> Paragraph<'a> {
+    let mut spans = vec![Span::styled(format!("{} ", runner.spinner()), Style::default().fg(theme.accent))];
+    if let Some(task) = runner.running.first() {
+        spans.push(Span::raw(task.label.clone()));
+    }
+    if runner.running.len() > 1 {
+        spans.push(Span::styled(format!("  (+{} more)", runner.running.len() - 1), theme.muted()));
+    }
+    spans.push(Span::styled("  Ctrl+T lists tasks", theme.muted()));
+
+    Paragraph::new(Spans::from(spans)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(false))
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn render_task_list<'a>(runner: &tasks::TaskRunner, theme: &Theme) -> List<'a> {
+    let items: Vec<_> = runner
+        .running
+        .iter()
+        .map(|task| {
+            let mut spans = vec![
+                Span::raw(task.label.clone()),
+                Span::styled(format!("  {}s", task.started_at.elapsed().as_secs()), theme.muted()),
+            ];
+            if task.is_cancelled() {
+                spans.push(Span::styled("  cancelling…", Style::default().fg(theme.warning)));
+            }
+            ListItem::new(Spans::from(spans))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(true))
+                .title("Running tasks (x cancels, Esc closes)")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(theme.selection())
+}
+
+/// Checks the git status of every directory, reporting the ones with uncommitted changes.
+fn scan_git_status(projects: Vec<(String, String)>, cancel: tasks::CancelFlag) -> tasks::Outcome {
+    let mut repositories = 0;
+    let mut dirty = Vec::new();
+    for (name, directory) in projects {
+        if cancel.is_cancelled() {
+            return Err("cancelled".to_string());
+        }
+        if let Some(status) = git::status(&directory) {
+            repositories += 1;
+            if status.is_dirty() {
+                dirty.push(name);
+            }
+        }
+    }
+    Ok(match dirty.len() {
+        0 => format!("All {} repositories are clean", repositories),
+        n => format!("{} of {} repositories have uncommitted changes: {}", n, repositories, dirty.join(", ")),
+    })
+}
+
+fn render_toast<'a>(toast: &Toast, theme: &Theme) -> Paragraph<'a> {
+    let (title, color) = match toast.kind {
+        ToastKind::Success => ("Done", theme.success),
+        ToastKind::Warning => ("Warning", theme.warning),
+        ToastKind::Error => ("Error", theme.error),
+    };
+
+    Paragraph::new(toast.text.clone()).wrap(Wrap { trim: true }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(Style::default().fg(color))
+            .title(Span::styled(title, Style::default().fg(color).add_modifier(Modifier::BOLD)))
+            .border_type(BorderType::Rounded),
+    )
+}
+
+/// Where a toast goes in the top-right corner of `area`, `top` rows down, tall enough
+/// for its text to wrap into.
+fn toast_area(text: &str, top: u16, area: Rect) -> Rect {
+    let length = text.chars().count() as u16;
+    let width = (length + 4).clamp(20, 50).min(area.width);
+    let lines = length.div_ceil(width.saturating_sub(4).max(1)).clamp(1, 4);
+    let y = area.y + top.min(area.height);
+    Rect::new(
+        area.x + area.width - width,
+        y,
+        width,
+        (lines + 2).min(area.y + area.height - y),
+    )
+}
+
+/// When `date` starts in the local timezone: at midnight, or where daylight saving time
+/// starts at midnight, like in Havana, at the first hour after it that exists.
+fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+    let midnight = date.and_time(NaiveTime::default());
+    (0..24)
+        .find_map(|hour| (midnight + chrono::Duration::hours(hour)).and_local_timezone(Local).earliest())
+        .map_or_else(|| Utc.from_utc_datetime(&midnight), |start| start.with_timezone(&Utc))
+}
+
+/// Time tracked per project and day during the current week (Monday to Sunday).
+fn render_week<'a>(theme: &Theme) -> Table<'a> {
+    let today = Local::now().date_naive();
+    let monday = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let bounds: Vec<_> = (0..=7).map(|offset| start_of_day(monday + chrono::Duration::days(offset))).collect();
+
+    let mut totals = vec![chrono::Duration::zero(); 8];
+    let mut rows = Vec::new();
+    for project in read_db().unwrap_or_default() {
+        let days: Vec<chrono::Duration> = (0..7)
+            .map(|day| {
+                project
+                    .time_entries
+                    .iter()
+                    .map(|entry| entry.overlap(bounds[day], bounds[day + 1]))
+                    .fold(chrono::Duration::zero(), |total, spent| total + spent)
+            })
+            .collect();
+        let week = days.iter().fold(chrono::Duration::zero(), |total, spent| total + *spent);
+        if week.is_zero() {
+            continue;
+        }
+        for (total, spent) in totals.iter_mut().zip(days.iter().chain([&week])) {
+            *total = *total + *spent;
+        }
+
+        let mut cells = vec![Cell::from(Span::raw(project.name))];
+        cells.extend(days.into_iter().chain([week]).map(|spent| Cell::from(Span::raw(format_duration(spent)))));
+        rows.push(Row::new(cells));
+    }
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut total_cells = vec![Cell::from(Span::styled("Total", bold))];
+    total_cells.extend(totals.into_iter().map(|spent| Cell::from(Span::styled(format_duration(spent), bold))));
+    rows.push(Row::new(total_cells));
+
+    let header: Vec<_> = ["Project", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun", "Week"]
+        .iter()
+        .map(|title| Cell::from(Span::styled(*title, bold)))
+        .collect();
+
+    Table::new(rows)
+        .header(Row::new(header))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(false))
+                .title(format!("Week of {}", monday))
+                .border_type(BorderType::Plain),
+        )
+        .widths(&[
+            Constraint::Percentage(20),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+            Constraint::Percentage(10),
+        ])
+}
+
+/// One row per repository with its branch, changed files, commits to push and pull, and
+/// last commit. Rows that need attention come first, in the warning color.
+fn render_repositories<'a>(mut repositories: Vec<(Project, Option<git::GitStatus>)>, theme: &Theme) -> Table<'a> {
+    repositories.sort_by_key(|(project, status)| {
+        (!status.as_ref().is_some_and(|s| s.needs_attention()), project.name.to_lowercase())
+    });
+    let attention = repositories.iter().filter(|(_, status)| status.as_ref().is_some_and(|s| s.needs_attention())).count();
+
+    let count = |n: u32| if n == 0 { String::new() } else { n.to_string() };
+    let rows: Vec<Row> = repositories
+        .into_iter()
+        .map(|(project, status)| {
+            let Some(status) = status else {
+                return Row::new(vec![Cell::from(project.name), Cell::from(Span::styled("checking…", theme.muted()))]);
+            };
+            let style = if status.needs_attention() { Style::default().fg(theme.warning) } else { Style::default() };
+            let last_commit = match &status.last_commit {
+                Some(commit) => format!("{} ({})", commit.subject, relative_time(commit.committed_at)),
+                None => String::new(),
+            };
+            Row::new(vec![
+                Cell::from(project.name),
+                Cell::from(status.branch.clone()),
+                Cell::from(count(status.changed)),
+                Cell::from(count(status.ahead)),
+                Cell::from(count(status.behind)),
+                Cell::from(Span::styled(last_commit, theme.muted())),
+            ])
+            .style(style)
+        })
+        .collect();
+
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let header: Vec<_> = ["Project", "Branch", "Changed", "Unpushed", "Unpulled", "Last commit"]
+        .iter()
+        .map(|title| Cell::from(Span::styled(*title, bold)))
+        .collect();
+
+    Table::new(rows)
+        .header(Row::new(header))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(false))
+                .title(format!("Repositories ({} need attention)", attention))
+                .border_type(BorderType::Plain),
+        )
+        .widths(&[
+            Constraint::Percentage(20),
+            Constraint::Percentage(15),
+            Constraint::Percentage(8),
+            Constraint::Percentage(8),
+            Constraint::Percentage(8),
+            Constraint::Percentage(41),
+        ])
+}
+
+/// Width of the project names in the timeline, and of each month after them.
+const TIMELINE_NAME_WIDTH: u16 = 20;
+const TIMELINE_MONTH_WIDTH: u16 = 4;
+
+/// Counts months from year 0, so that months can be compared and subtracted.
+fn month_index(at: DateTime<Utc>) -> i32 {
+    let local = at.with_timezone(&Local);
+    local.year() * 12 + local.month0() as i32
+}
+
+/// The months in which work on `project` happened: when time was tracked and when it
+/// was last opened.
+fn active_months(project: &Project) -> Vec<i32> {
+    let mut months: Vec<i32> = project
+        .time_entries
+        .iter()
+        .map(|entry| month_index(entry.start))
+        .chain(project.last_opened_at.map(month_index))
+        .collect();
+    months.sort_unstable();
+    months.dedup();
+    months
+}
+
+/// One line per project along the last months, as many as fit into `width`: a bar from
+/// the month it was created to the last month with activity, so that stalled projects
+/// end early.
+fn render_timeline<'a>(projects: &[Project], width: u16, theme: &Theme) -> Paragraph<'a> {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(theme.text())
+        .border_style(theme.border(false))
+        .title("Timeline (● created, ■ active)")
+        .border_type(BorderType::Plain);
+    if projects.is_empty() {
+        return Paragraph::new(Span::styled("Nothing here", theme.muted())).block(block);
+    }
+
+    let mut projects: Vec<&Project> = projects.iter().collect();
+    projects.sort_by_key(|project| project.created_at);
+    let now = month_index(Utc::now());
+    let first = month_index(projects[0].created_at);
+    let fitting = (width.saturating_sub(TIMELINE_NAME_WIDTH + 2) / TIMELINE_MONTH_WIDTH).max(1) as i32;
+    let start = first.max(now - fitting + 1);
+    let cell_width = TIMELINE_MONTH_WIDTH as usize;
+
+    // The years above the months, marked where they start.
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    let mut years = vec![Span::raw(" ".repeat(TIMELINE_NAME_WIDTH as usize))];
+    let mut months = years.clone();
+    for month in start..=now {
+        let year = if month % 12 == 0 || month == start { (month / 12).to_string() } else { String::new() };
+        let name = NaiveDate::from_ymd_opt(month / 12, month as u32 % 12 + 1, 1)
+            .map(|date| date.format("%b").to_string())
+            .unwrap_or_default();
+        years.push(Span::styled(format!("{:<width$}", year, width = cell_width), bold));
+        months.push(Span::styled(format!("{:<width$}", name, width = cell_width), bold));
+    }
+
+    let mut lines = vec![Spans::from(years), Spans::from(months)];
+    for project in projects {
+        let created = month_index(project.created_at);
+        let active = active_months(project);
+        let last = active.last().copied().unwrap_or(created).max(created);
+        let color = theme.status(project.status);
+        let mut spans = vec![Span::raw(format!(
+            "{:<width$.width$}",
+            project.name,
+            width = TIMELINE_NAME_WIDTH as usize - 1
+        ))];
+        spans.push(Span::raw(" "));
+        for month in start..=now {
+            let continues = month < last;
+            let (symbol, style) = if month == created {
+                ("●", Style::default().fg(theme.accent))
+            } else if active.contains(&month) {
+                ("■", Style::default().fg(color))
+            } else if month > created && month < last {
+                ("─", Style::default().fg(color))
+            } else {
+                (" ", Style::default())
+            };
+            let fill = if continues && month >= created { "─" } else { " " };
+            spans.push(Span::styled(symbol, style));
+            spans.push(Span::styled(fill.repeat(cell_width - 1), Style::default().fg(color)));
+        }
+        lines.push(Spans::from(spans));
+    }
+
+    Paragraph::new(lines).block(block)
+}
+
+fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes();
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// How long ago `at` was, like "3 days ago".
+fn relative_time(at: DateTime<Utc>) -> String {
+    let elapsed = Utc::now() - at;
+    let (amount, unit) = if elapsed.num_minutes() < 1 {
+        return "just now".to_string();
+    } else if elapsed.num_hours() < 1 {
+        (elapsed.num_minutes(), "minute")
+    } else if elapsed.num_days() < 1 {
+        (elapsed.num_hours(), "hour")
+    } else if elapsed.num_days() < 30 {
+        (elapsed.num_days(), "day")
+    } else if elapsed.num_days() < 365 {
+        (elapsed.num_days() / 30, "month")
+    } else {
+        (elapsed.num_days() / 365, "year")
+    };
+    format!("{} {}{} ago", amount, unit, if amount == 1 { "" } else { "s" })
+}
+
+fn format_timestamp(at: DateTime<Utc>, display: DisplayOptions) -> String {
+    if display.exact_times {
+        at.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string()
+    } else {
+        relative_time(at)
+    }
+}
+
+fn render_board_column<'a>(status: Status, projects: &[Project], focused: bool, theme: &Theme) -> List<'a> {
+    let items: Vec<_> = projects
+        .iter()
+        .map(|project| {
+            let mut details = vec![Span::styled(
+                format!("{} {}", project.priority.indicator(), project.priority.label()),
+                Style::default().fg(theme.priority(project.priority)),
+            )];
+            if let Some(due) = project.due_date {
+                let style = if project.is_overdue() { theme.error() } else { theme.muted() };
+                details.push(Span::styled(format!("  due {}", due), style));
+            }
+            for tag in &project.tags {
+                details.push(Span::styled(format!(" #{}", tag), theme.muted()));
+            }
+            ListItem::new(vec![
+                Spans::from(Span::styled(project.name.clone(), Style::default().add_modifier(Modifier::BOLD))),
+                Spans::from(details),
+                Spans::from(""),
+            ])
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(focused))
+                .title(Span::styled(
+                    format!("{} ({})", status.label(), projects.len()),
+                    Style::default().fg(theme.status(status)),
+                ))
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(if focused { theme.selection() } else { Style::default() })
+}
+
+fn render_links<'a>(project: Option<&Project>, focused: bool, theme: &Theme) -> List<'a> {
+    let links = match project {
+        Some(project) => project_links(project).unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let items: Vec<_> = links
+        .into_iter()
+        .map(|(label, target)| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", label), theme.muted()),
+                Span::raw(target.name),
+            ]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(focused))
+                .title("Links")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(if focused {
+            theme.selection()
+        } else {
+            Style::default()
+        })
+}
+
+/// The line about the last commit at the bottom of the detail pane, empty outside of
+/// repositories.
+fn render_last_commit<'a>(git_status: Option<&git::GitStatus>, theme: &Theme) -> Paragraph<'a> {
+    let line = match git_status.map(|status| &status.last_commit) {
+        Some(Some(commit)) => Spans::from(vec![
+            Span::styled(format!("{} ", commit.hash), Style::default().fg(theme.accent)),
+            Span::raw(commit.subject.clone()),
+            Span::styled(
+                format!(" ({}, {})", commit.author, relative_time(commit.committed_at)),
+                theme.muted(),
+            ),
+        ]),
+        Some(None) => Spans::from(Span::styled("No commits yet", theme.muted())),
+        None => Spans::default(),
+    };
+    Paragraph::new(line)
+}
+
+fn render_remotes<'a>(remotes: &[git::Remote], focused: bool, theme: &Theme) -> List<'a> {
+    let items: Vec<_> = remotes
+        .iter()
+        .map(|remote| {
+            ListItem::new(Spans::from(vec![
+                Span::styled(format!("{} ", remote.name), theme.muted()),
+                Span::raw(remote.url.clone()),
+            ]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(focused))
+                .title("Remotes")
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(if focused {
+            theme.selection()
+        } else {
+            Style::default()
+        })
+}
+
+fn render_readme<'a>(content: Option<&str>, theme: &Theme) -> Paragraph<'a> {
+    let lines = match content {
+        Some(content) => markdown::render(content, theme),
+        None => vec![Spans::from(Span::styled("No README in this directory", theme.muted()))],
+    };
+
+    Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(false))
+            .title("README")
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn render_preview<'a>(entries: &io::Result<Vec<String>>, theme: &Theme) -> List<'a> {
+    let items: Vec<_> = match entries {
+        Ok(entries) => entries
+            .iter()
+            .map(|entry| {
+                let style = if entry.ends_with('/') {
+                    Style::default().fg(theme.title)
+                } else {
+                    theme.text()
+                };
+                ListItem::new(Span::styled(entry.clone(), style))
+            })
+            .collect(),
+        Err(err) => vec![ListItem::new(Span::styled(err.to_string(), theme.error()))],
+    };
+
+    List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .style(theme.text())
+            .border_style(theme.border(false))
+            .title("Files")
+            .border_type(BorderType::Plain),
+    )
+}
+
+fn render_todos<'a>(project: Option<&Project>, focused: bool, theme: &Theme) -> List<'a> {
+    let todos = project.map(|p| p.todos.clone()).unwrap_or_default();
+    let open = todos.iter().filter(|todo| !todo.done).count();
+
+    let items: Vec<_> = todos
+        .into_iter()
+        .map(|todo| {
+            let (check, style) = if todo.done {
+                ("[x] ", theme.muted().add_modifier(Modifier::CROSSED_OUT))
+            } else {
+                ("[ ] ", theme.text())
+            };
+            ListItem::new(Spans::from(vec![Span::raw(check), Span::styled(todo.text, style)]))
+        })
+        .collect();
+
+    List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(focused))
+                .title(format!("TODO ({} open)", open))
+                .border_type(BorderType::Plain),
+        )
+        .highlight_style(if focused {
+            theme.selection()
+        } else {
+            Style::default()
+        })
+}
+
+// Everything the list and the detail are drawn from, taking them apart wouldn't help.
+#[allow(clippy::too_many_arguments)]
+fn render_projects<'a>(
+    project_list_state: &ListState,
+    view: &ListView,
+    marked: &[String],
+    git_status: Option<&git::GitStatus>,
+    languages: &mut languages::Cache,
+    display: DisplayOptions,
+    detail_width: u16,
+    theme: &Theme,
+) -> (List<'a>, Option<Table<'a>>) {
+    let list_name = view.workspace.as_deref().unwrap_or("Projects");
+    let mut title = match view.status_filter {
+        // While searching, the match count is more useful than the sort mode in the narrow title.
+        _ if !view.search.is_empty() => {
+            let (matched, total) = search_match_counts(view).unwrap_or_default();
+            format!("{} ({}/{} matches) /{}", list_name, matched, total, view.search)
+        }
+        Some(status) => format!("{} ({}, by {})", list_name, status.label(), view.sort_mode.label()),
+        None => format!("{} (by {})", list_name, view.sort_mode.label()),
+    };
+    if !marked.is_empty() {
+        title.push_str(&format!(" {} marked", marked.len()));
+    }
+    let projects = Block::default()
+        .borders(Borders::ALL)
+        .style(theme.text())
+                .border_style(theme.border(false))
+        .title(title)
+        .border_type(BorderType::Plain);
+
+    let (rows, lines) = grouped_tree(view).unwrap_or_default();
+    let matcher = SkimMatcherV2::default();
+
+    let mut items = Vec::with_capacity(lines.len());
+    for line in &lines {
+        let row = match line {
+            ListLine::Header { name, depth, count, statuses, collapsed, .. } => {
+                let marker = if *collapsed { "▸" } else { "▾" };
+                let mut spans = vec![Span::styled(
+                    format!("{}{} {} ({})", "  ".repeat(*depth), marker, name, count),
+                    Style::default().fg(theme.title).add_modifier(Modifier::BOLD),
+                )];
+                // The rows aren't there to tell, so a collapsed section sums them up.
+                if *collapsed {
+                    let summary: Vec<_> = statuses
+                        .iter()
+                        .map(|(status, count)| format!("{} {}", count, status.label()))
+                        .collect();
+                    spans.push(Span::styled(format!(" {}", summary.join(", ")), Style::default().fg(theme.muted)));
+                }
+                items.push(ListItem::new(Spans::from(spans)));
+                continue;
+            }
+            ListLine::Row(index) => &rows[*index],
+        };
+        items.push({
+            let project = &row.project;
+            let marker = match (row.has_children, project.collapsed) {
+                (true, true) => "▸ ",
+                (true, false) => "▾ ",
+                (false, _) => "  ",
+            };
+            let mut spans = vec![
+                if marked.contains(&project.id) {
+                    Span::styled("● ", Style::default().fg(theme.accent))
+                } else {
+                    Span::raw("  ")
+                },
+                Span::raw(format!("{}{}", "  ".repeat(row.depth + row.group.len()), marker)),
+                Span::styled(
+                    format!("{} ", project.priority.indicator()),
+                    Style::default().fg(theme.priority(project.priority)),
+                ),
+                Span::styled(
+                    format!("[{}] ", project.status.label()),
+                    Style::default().fg(theme.status(project.status)),
+                ),
+            ];
+            let name_style = if project.is_overdue() {
+                theme.error()
+            } else {
+                Style::default()
+            };
+            spans.extend(highlight_matches(&matcher, &project.name, &view.search, name_style, theme));
+            if let Some(alias) = &project.alias {
+                spans.push(Span::styled(
+                    format!(" ({})", alias),
+                    theme.muted(),
+                ));
+            }
+            for language in languages.get(&project.directory) {
+                spans.push(Span::styled(
+                    format!(" {}", language.badge(display.nerd_font)),
+                    Style::default().fg(theme.accent),
+                ));
+            }
+            for tag in &project.tags {
+                spans.push(Span::styled(" #", theme.muted()));
+                spans.extend(highlight_matches(&matcher, tag, &view.search, theme.muted(), theme));
+            }
+            if project.is_missing() {
+                spans.push(Span::styled(" ⚠", Style::default().fg(theme.warning)));
+            }
+            if project.is_timer_running() {
+                spans.push(Span::styled(" ⏱", Style::default().fg(theme.timer)));
+            }
+            let open_todos = project.open_todos();
+            if open_todos > 0 {
+                spans.push(Span::styled(
+                    format!(" [{}]", open_todos),
+                    Style::default().fg(theme.todo_count),
+                ));
+            }
+            ListItem::new(Spans::from(spans))
+        });
+    }
+
+    let list = List::new(items).block(projects).highlight_style(theme.selection());
+
+    let project_list: Vec<Project> = rows.into_iter().map(|row| row.project).collect();
+
+    // Display selected project if there's any selected
+    let selected_project = project_list_state
+        .selected()
+        .and_then(|selected| project_list.get(selected).or_else(|| project_list.first()));
+
+    if let Some(selected_project) = selected_project {
+        let selected_project = selected_project.clone();
+        let overdue = selected_project.is_overdue();
+        let tracked = format_duration(selected_project.total_time());
+        let width = directory_width(detail_width, display);
+        let directory: Vec<Spans> = if display.full_paths {
+            wrap_path(&selected_project.directory, width).into_iter().map(Spans::from).collect()
+        } else {
+            vec![Spans::from(shorten_path(&selected_project.directory, width))]
+        };
+        let row_height = directory.len() as u16;
+
+        let due = Span::styled(
+            selected_project.due_date.map(|d| d.to_string()).unwrap_or_default(),
+            if overdue {
+                theme.error()
+            } else {
+                Style::default()
+            },
+        );
+        let status = Span::styled(
+            selected_project.status.label(),
+            Style::default().fg(theme.status(selected_project.status)),
+        );
+        // Narrow panes only get the essential columns, unless they're shown as rows.
+        let compact = !display.vertical_detail && detail_width < NARROW_WIDTH;
+        let columns: Vec<(&str, Cell)> = if compact {
+            vec![
+                ("Name", Cell::from(Span::raw(selected_project.name))),
+                ("Directory", Cell::from(Text::from(directory))),
+                ("Status", Cell::from(status)),
+                ("Due", Cell::from(due)),
+            ]
+        } else {
+            vec![
+                ("ID", Cell::from(Span::raw(selected_project.short_id().to_string()))),
+                ("Name", Cell::from(Span::raw(selected_project.name))),
+                ("Directory", Cell::from(Text::from(directory))),
+                ("Status", Cell::from(status)),
+                (
+                    "Priority",
+                    Cell::from(Span::styled(
+                        selected_project.priority.label(),
+                        Style::default().fg(theme.priority(selected_project.priority)),
+                    )),
+                ),
+                ("Due", Cell::from(due)),
+                ("Tracked", Cell::from(Span::raw(tracked))),
+                (
+                    "Size",
+                    Cell::from(Span::raw(
+                        selected_project.disk_usage.map(|usage| usage::format_size(usage.bytes)).unwrap_or_default(),
+                    )),
+                ),
+                (
+                    "Git",
+                    Cell::from(match git_status {
+                        Some(status) if status.is_dirty() => {
+                            Span::styled(status.summary(), Style::default().fg(theme.accent))
+                        }
+                        Some(status) => Span::raw(status.summary()),
+                        None => Span::raw(""),
+                    }),
+                ),
+                ("Repository", Cell::from(Span::raw(selected_project.repo_url.unwrap_or_default()))),
+                (
+                    "Created",
+                    Cell::from(Span::raw(format_timestamp(selected_project.created_at, display))),
+                ),
+                (
+                    "Opened",
+                    Cell::from(Span::raw(
+                        selected_project
+                            .last_opened_at
+                            .map(|at| format_timestamp(at, display))
+                            .unwrap_or_else(|| "never".to_string()),
+                    )),
+                ),
+            ]
+        };
+        let languages = languages.get(&selected_project.directory);
+        let detail_title = if languages.is_empty() {
+            "Detail".to_string()
+        } else {
+            let names: Vec<String> = languages
+                .iter()
+                .map(|language| {
+                    if display.nerd_font {
+                        format!("{} {}", language.badge(true), language.name)
+                    } else {
+                        language.name.to_string()
+                    }
+                })
+                .collect();
+            format!("Detail ({})", names.join(", "))
+        };
+        let label = |title: &'a str| Cell::from(Span::styled(title, Style::default().add_modifier(Modifier::BOLD)));
+
+        let project_detail = if display.vertical_detail {
+            let rows: Vec<Row> = columns
+                .into_iter()
+                .map(|(title, cell)| {
+                    let height = if title == "Directory" { row_height } else { 1 };
+                    Row::new(vec![label(title), cell]).height(height)
+                })
+                .collect();
+            Table::new(rows).widths(&VERTICAL_DETAIL_WIDTHS)
+        } else {
+            let header = Row::new(columns.iter().map(|(title, _)| label(title)).collect::<Vec<_>>());
+            Table::new(vec![Row::new(columns.into_iter().map(|(_, cell)| cell).collect::<Vec<_>>())
+                .height(row_height)])
+            .header(header)
+            .widths(if compact { &COMPACT_DETAIL_WIDTHS } else { &DETAIL_WIDTHS })
+        }
+        .block(
+                Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(false))
+                .title(detail_title)
+                .border_type(BorderType::Plain),
+        );
+
+        (list, Some(project_detail))
+    } else {
+        let project_detail = Some(Table::new(vec![]).block(
+                Block::default()
+                .borders(Borders::ALL)
+                .style(theme.text())
+                .border_style(theme.border(false))
+                .title("No project selected")
+                .border_type(BorderType::Plain),
+        ));
+
+        (list, project_detail)
+    }
+}
+
+fn select_next_project(project_list_state: &mut ListState, view: &ListView) -> Result<(), Error> {
+    let amount_projects = visible_projects(view)?.len();
+    if amount_projects == 0 {
+        return Ok(());
+    }
+    match project_list_state.selected() {
+        Some(selected) if selected < amount_projects - 1 => project_list_state.select(Some(selected + 1)),
+        _ => project_list_state.select(Some(0)),
+    }
+    Ok(())
+}
+
+fn select_previous_project(project_list_state: &mut ListState, view: &ListView) -> Result<(), Error> {
+    let amount_projects = visible_projects(view)?.len();
+    if amount_projects == 0 {
+        return Ok(());
+    }
+    match project_list_state.selected() {
+        Some(selected) if selected > 0 => project_list_state.select(Some(selected - 1)),
+        _ => project_list_state.select(Some(amount_projects - 1)),
+    }
+    Ok(())
+}
+
+/// Moves the selection by `offset` rows, stopping at the ends of the list instead of wrapping.
+fn select_project_by_offset(project_list_state: &mut ListState, view: &ListView, offset: isize) -> Result<(), Error> {
+    let amount_projects = visible_projects(view)?.len();
+    if amount_projects == 0 {
+        return Ok(());
+    }
+    let selected = project_list_state.selected().unwrap_or(0);
+    let target = selected.saturating_add_signed(offset).min(amount_projects - 1);
+    project_list_state.select(Some(target));
+    Ok(())
+}
+
+/// Selects the next project after the selected one whose name starts with `letter`, wrapping
+/// around at the end of the list.
+fn select_project_by_letter(project_list_state: &mut ListState, view: &ListView, letter: char) -> Result<(), Error> {
+    let projects = visible_projects(view)?;
+    let start = project_list_state.selected().map_or(0, |i| i + 1);
+    let letter = letter.to_lowercase().to_string();
+    let found = (0..projects.len())
+        .map(|i| (start + i) % projects.len())
+        .find(|&i| projects[i].name.to_lowercase().starts_with(&letter));
+    if let Some(index) = found {
+        project_list_state.select(Some(index));
+    }
+    Ok(())
+}
+
+fn contains(area: Rect, column: u16, row: u16) -> bool {
+    column >= area.x && column < area.x + area.width && row >= area.y && row < area.y + area.height
+}
+
+/// Index of the tab under `column`, following how the Tabs widget lays out its titles:
+/// one space of padding on each side and a one-column divider in between.
+fn clicked_tab(titles: &[&str], area: Rect, column: u16) -> Option<usize> {
+    let mut x = area.x + 1;
+    for (i, title) in titles.iter().enumerate() {
+        let start = x + 1;
+        let end = start + title.chars().count() as u16;
+        if column >= x && column <= end {
+            return Some(i);
+        }
+        x = end + 2;
+    }
+    None
+}
+
+/// Below this many columns, panes are stacked and the detail table shows fewer columns.
+const NARROW_WIDTH: u16 = 80;
+
+/// Widths of the detail table's columns, in percent.
+const DETAIL_WIDTHS: [Constraint; 12] = [
+    Constraint::Percentage(5),
+    Constraint::Percentage(9),
+    Constraint::Percentage(15),
+    Constraint::Percentage(8),
+    Constraint::Percentage(7),
+    Constraint::Percentage(8),
+    Constraint::Percentage(7),
+    Constraint::Percentage(7),
+    Constraint::Percentage(8),
+    Constraint::Percentage(8),
+    Constraint::Percentage(9),
+    Constraint::Percentage(9),
+];
+const COMPACT_DETAIL_WIDTHS: [Constraint; 4] = [
+    Constraint::Percentage(25),
+    Constraint::Percentage(40),
+    Constraint::Percentage(15),
+    Constraint::Percentage(20),
+];
+const VERTICAL_DETAIL_WIDTHS: [Constraint; 2] = [Constraint::Length(11), Constraint::Min(1)];
+
+/// Height of the detail pane for a directory shown on `directory_lines` lines.
+fn detail_height(display: DisplayOptions, directory_lines: u16) -> u16 {
+    if display.vertical_detail {
+        // A row per field but the directory, the last commit and the borders.
+        DETAIL_WIDTHS.len() as u16 - 1 + directory_lines + 1 + 2
+    } else {
+        // The header, the last commit below the row and the borders.
+        directory_lines + 4
+    }
+}
+
+/// Width of the directory column of the detail table in a pane `detail_width` wide.
+fn directory_width(detail_width: u16, display: DisplayOptions) -> usize {
+    let inner = detail_width.saturating_sub(2) as usize;
+    if display.vertical_detail {
+        return inner.saturating_sub(12).max(1);
+    }
+    let percent = if detail_width < NARROW_WIDTH { 40 } else { 15 };
+    (inner * percent / 100).saturating_sub(1).max(1)
+}
+
+/// Fits a path into `width` characters by abbreviating the home directory to `~` and leaving
+/// out directories in the middle, like `~/dev/…/project`.
+fn shorten_path(path: &str, width: usize) -> String {
+    let home = home_dir().unwrap_or_default();
+    let home = home.to_string_lossy();
+    let path = match path.strip_prefix(home.as_ref()) {
+        Some(rest) if !home.is_empty() && (rest.is_empty() || rest.starts_with('/')) => format!("~{}", rest),
+        _ => path.to_string(),
+    };
+    if path.chars().count() <= width {
+        return path;
+    }
+
+    // Keep the first two components and as many of the last ones as fit, then only the last ones.
+    let parts: Vec<&str> = path.split('/').collect();
+    let head = parts[..2.min(parts.len())].join("/");
+    let candidates = (3..parts.len())
+        .map(|start| format!("{}/…/{}", head, parts[start..].join("/")))
+        .chain((1..parts.len()).map(|start| format!("…/{}", parts[start..].join("/"))));
+    for shortened in candidates {
+        if shortened.chars().count() <= width {
+            return shortened;
+        }
+    }
+    // Not even the last component fits: keep its end.
+    let skip = path.chars().count() + 1 - width.max(1);
+    format!("…{}", path.chars().skip(skip).collect::<String>())
+}
+
+/// Splits a path into lines of at most `width` characters.
+fn wrap_path(path: &str, width: usize) -> Vec<String> {
+    let chars: Vec<char> = path.chars().collect();
+    chars.chunks(width.max(1)).map(|line| line.iter().collect()).collect()
+}
+
+/// Expands a leading `~` to the home directory.
+fn expand_tilde(path: &str) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => {
+            let home = home_dir().unwrap_or_default();
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Keeps the data next to the executable for the rest of the run.
+fn set_portable() {
+    PORTABLE.store(true, Ordering::Relaxed);
+}
+
+/// Refuses changes to the projects for the rest of the run.
+fn set_read_only() {
+    READ_ONLY.store(true, Ordering::Relaxed);
+}
+
+fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Starts the UI without the session of the last run.
+fn set_fresh() {
+    FRESH.store(true, Ordering::Relaxed);
+}
+
+/// Where the DB, config and state files are: `~/.config/whisk`, or the directory of the
+/// executable in portable mode, with `--portable` or a `whisk-portable` file there.
+fn data_dir() -> PathBuf {
+    let executable_dir = std::env::current_exe()
+        .ok()
+        .and_then(|executable| executable.canonicalize().ok())
+        .and_then(|executable| executable.parent().map(Path::to_path_buf));
+    if let Some(dir) = executable_dir {
+        if PORTABLE.load(Ordering::Relaxed) || dir.join(PORTABLE_MARKER).exists() {
+            return dir;
+        }
+    }
+    PathBuf::from(home_dir().unwrap_or_default().to_string_lossy().to_string() + DB_PATH)
+}
+
+/// The DB file, `$WHISK_DB` if set.
+fn db_file() -> PathBuf {
+    match std::env::var_os(DB_ENV) {
+        Some(path) if !path.is_empty() => PathBuf::from(path),
+        _ => data_dir().join("db.json"),
+    }
+}
+
+/// The DB file, created empty along with its directory if it doesn't exist yet, except in
+/// read-only mode.
+fn get_db_path() -> Result<PathBuf, Error> {
+    let db_file = db_file();
+    if is_read_only() {
+        return Ok(db_file);
+    }
+    if let Some(db_path) = db_file.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        fs::create_dir_all(db_path)?;
+    }
+    if !db_file.exists() {
+        fs::write(&db_file, b"[]")?;
+    }
+    Ok(db_file)
+}
+
+fn read_db() -> Result<Vec<Project>, Error> {
+    let db_file = get_db_path()?;
+    // Without a DB there are no projects yet, and read-only mode doesn't create one.
+    if is_read_only() && !db_file.exists() {
+        return Ok(Vec::new());
+    }
+    let db_content = fs::read_to_string(db_file)?;
+    let parsed: Vec<Project> = serde_json::from_str(&db_content)?;
+    Ok(parsed)
+}
+
+fn write_db(projects: &[Project]) -> Result<(), Error> {
+    if is_read_only() {
+        return Err(Error::ReadOnly);
+    }
+    fs::write(get_db_path()?, &serde_json::to_vec(projects)?)?;
+    Ok(())
+}
+
+/// A project as it appears in the list, with its nesting information.
+struct TreeRow {
+    project: Project,
+    depth: usize,
+    has_children: bool,
+    /// The sections the row is listed in, those of its top-level project.
+    group: Vec<String>,
+}
+
+/// A line of the drawn project list.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum ListLine {
+    /// A section header. `key` is what it's collapsed by and `count` how many projects it has,
+    /// `statuses` how many of them have each status.
+    Header {
+        key: String,
+        name: String,
+        depth: usize,
+        count: usize,
+        statuses: Vec<(Status, usize)>,
+        collapsed: bool,
+    },
+    /// The visible row at this index.
+    Row(usize),
+}
+
+/// Projects shown in the list, narrowed down by the status filter and ordered by the sort mode.
+fn visible_projects(view: &ListView) -> Result<Vec<Project>, Error> {
+    Ok(visible_tree(view)?.into_iter().map(|row| row.project).collect())
+}
+
+/// The rows of the list, without those in collapsed sections.
+fn visible_tree(view: &ListView) -> Result<Vec<TreeRow>, Error> {
+    Ok(grouped_tree(view)?.0)
+}
+
+/// The visible rows of the list, and the lines they're drawn on with the section headers
+/// in between.
+fn grouped_tree(view: &ListView) -> Result<(Vec<TreeRow>, Vec<ListLine>), Error> {
+    let rows = project_tree(view)?;
+    // Search results are shown even when their section is collapsed.
+    let is_collapsed = |key: &String| view.search.is_empty() && view.collapsed_groups.contains(key);
+
+    let mut lines = Vec::with_capacity(rows.len());
+    let mut hidden = Vec::with_capacity(rows.len());
+    let mut shown = 0;
+    for (i, row) in rows.iter().enumerate() {
+        let previous: &[String] = if i == 0 { &[] } else { &rows[i - 1].group };
+        let unchanged = previous.iter().zip(&row.group).take_while(|(a, b)| a == b).count();
+        let mut is_hidden = false;
+        for depth in 0..row.group.len() {
+            let path = &row.group[..=depth];
+            let key = view.group_by.key(path);
+            let collapsed = is_collapsed(&key);
+            if depth >= unchanged {
+                let in_section: Vec<_> = rows.iter().filter(|r| r.group.starts_with(path)).collect();
+                lines.push(ListLine::Header {
+                    count: in_section.len(),
+                    statuses: Status::ALL
+                        .iter()
+                        .map(|&status| (status, in_section.iter().filter(|r| r.project.status == status).count()))
+                        .filter(|&(_, count)| count > 0)
+                        .collect(),
+                    name: row.group[depth].clone(),
+                    key,
+                    depth,
+                    collapsed,
+                });
+            }
+            if collapsed {
+                is_hidden = true;
+                break;
+            }
+        }
+        if !is_hidden {
+            lines.push(ListLine::Row(shown));
+            shown += 1;
+        }
+        hidden.push(is_hidden);
+    }
+
+    let rows = rows.into_iter().zip(hidden).filter(|(_, hidden)| !hidden).map(|(row, _)| row).collect();
+    Ok((rows, lines))
+}
+
+fn sort_projects(projects: &mut [Project], sort_mode: SortMode) {
+    match sort_mode {
+        SortMode::Added => projects.sort_by_key(|project| project.created_at),
+        SortMode::Name => projects.sort_by_key(|project| project.name.to_lowercase()),
+        // Recently opened first, never opened last.
+        SortMode::LastOpened => projects.sort_by_key(|project| std::cmp::Reverse(project.last_opened_at)),
+        SortMode::Path => projects.sort_by(|a, b| a.directory.cmp(&b.directory)),
+        // Stable sorts keep the DB order between equal keys.
+        SortMode::Priority => projects.sort_by_key(|project| std::cmp::Reverse(project.priority)),
+        SortMode::Manual => projects.sort_by_key(|project| project.order),
+        // Projects without a deadline go last.
+        SortMode::DueDate => projects.sort_by_key(|project| (project.due_date.is_none(), project.due_date)),
+        // Unmeasured projects go last.
+        SortMode::Size => projects.sort_by_key(|project| std::cmp::Reverse(project.disk_usage.map(|usage| usage.bytes))),
+        SortMode::Frecency => {
+            let now = Utc::now();
+            projects.sort_by_key(|project| std::cmp::Reverse(project.frecency(now)));
+        }
+    }
+}
+
+/// Builds the list as a tree: every sub-project follows its parent, one level deeper,
+/// unless the parent is collapsed. Sub-projects whose parent is filtered out are shown
+/// at the top level. When grouping, top-level projects are ordered by section.
+fn project_tree(view: &ListView) -> Result<Vec<TreeRow>, Error> {
+    let matcher = SkimMatcherV2::default();
+    let mut projects: Vec<Project> = read_db()?
+        .into_iter()
+        .filter(|project| matches_filters(view, project))
+        .filter(|project| view.search.is_empty() || matches_search(&matcher, project, &view.search))
+        .collect();
+    for project in projects.iter_mut().filter(|p| view.expanded.contains(&p.id)) {
+        project.collapsed = false;
+    }
+    sort_projects(&mut projects, view.sort_mode);
+
+    let is_shown = |id: &Option<String>| {
+        id.as_ref()
+            .is_some_and(|id| projects.iter().any(|p| &p.id == id))
+    };
+    let mut roots: Vec<&Project> = projects.iter().filter(|p| !is_shown(&p.parent_id)).collect();
+    // Sections in alphabetical order with the one for ungrouped projects last, keeping the
+    // sort order inside each.
+    roots.sort_by_key(|p| (!view.group_by.has_own_group(p), view.group_by.group(p)));
+    let mut groups: Vec<Vec<String>> = roots.iter().map(|p| view.group_by.group(p)).collect();
+    if view.group_by == GroupBy::Directory {
+        groups = merge_single_folders(&groups);
+    }
+
+    // Search results are shown even when their parent is collapsed.
+    let expand_all = !view.search.is_empty();
+    let mut rows = Vec::with_capacity(projects.len());
+    for (root, group) in roots.into_iter().zip(groups) {
+        let start = rows.len();
+        push_tree_rows(&projects, root, 0, expand_all, &mut rows);
+        for row in &mut rows[start..] {
+            row.group = group.clone();
+        }
+    }
+    Ok(rows)
+}
+
+/// Joins folders that only contain a single other folder into one section, so that
+/// `~` > `dev` > `clients` becomes `~/dev/clients`.
+fn merge_single_folders(groups: &[Vec<String>]) -> Vec<Vec<String>> {
+    groups
+        .iter()
+        .map(|group| {
+            let mut merged = Vec::new();
+            let Some(mut current) = group.first().cloned() else {
+                return merged;
+            };
+            for depth in 0..group.len() - 1 {
+                let next = &group[depth + 1];
+                let only_child = groups
+                    .iter()
+                    .filter(|other| other.starts_with(&group[..=depth]))
+                    .all(|other| other.get(depth + 1) == Some(next));
+                if only_child {
+                    current = format!("{}/{}", current.trim_end_matches('/'), next);
+                } else {
+                    merged.push(std::mem::replace(&mut current, next.clone()));
+                }
+            }
+            merged.push(current);
+            merged
+        })
+        .collect()
+}
+
+/// The statuses shown as columns of the board.
+const BOARD_COLUMNS: [Status; 3] = [Status::Active, Status::OnHold, Status::Done];
+
+/// The projects of each board column, narrowed down by the workspace and the search.
+fn board_columns(view: &ListView) -> Result<Vec<Vec<Project>>, Error> {
+    let matcher = SkimMatcherV2::default();
+    let projects: Vec<Project> = read_db()?
+        .into_iter()
+        .filter(|project| view.workspace.is_none() || project.workspace == view.workspace)
+        .filter(|project| view.search.is_empty() || matches_search(&matcher, project, &view.search))
+        .collect();
+    Ok(BOARD_COLUMNS
+        .iter()
+        .map(|status| projects.iter().filter(|p| p.status == *status).cloned().collect())
+        .collect())
+}
+
+/// Whether `project` passes the status and workspace filters of the list.
+fn matches_filters(view: &ListView, project: &Project) -> bool {
+    view.status_filter.is_none_or(|status| project.status == status)
+        && (view.workspace.is_none() || project.workspace == view.workspace)
+}
+
+/// How many projects match the search, out of those passing the other filters.
+fn search_match_counts(view: &ListView) -> Result<(usize, usize), Error> {
+    let matcher = SkimMatcherV2::default();
+    let candidates: Vec<Project> = read_db()?
+        .into_iter()
+        .filter(|project| matches_filters(view, project))
+        .collect();
+    let matched = candidates
+        .iter()
+        .filter(|project| matches_search(&matcher, project, &view.search))
+        .count();
+    Ok((matched, candidates.len()))
+}
+
+/// `text` split into spans, with the characters matched by the search highlighted.
+fn highlight_matches<'a>(
+    matcher: &SkimMatcherV2,
+    text: &str,
+    query: &str,
+    style: Style,
+    theme: &Theme,
+) -> Vec<Span<'a>> {
+    let matched = if query.is_empty() {
+        Vec::new()
+    } else {
+        matcher.fuzzy_indices(text, query).map(|(_, indices)| indices).unwrap_or_default()
+    };
+    if matched.is_empty() {
+        return vec![Span::styled(text.to_string(), style)];
+    }
+    text.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if matched.contains(&i) {
+                Span::styled(c.to_string(), style.fg(theme.search_match).add_modifier(Modifier::BOLD))
+            } else {
+                Span::styled(c.to_string(), style)
+            }
+        })
+        .collect()
+}
+
+fn matches_search(matcher: &SkimMatcherV2, project: &Project, query: &str) -> bool {
+    std::iter::once(&project.name)
+        .chain(std::iter::once(&project.directory))
+        .chain(project.tags.iter())
+        .any(|text| matcher.fuzzy_match(text, query).is_some())
+}
+
+fn push_tree_rows(
+    projects: &[Project],
+    project: &Project,
+    depth: usize,
+    expand_all: bool,
+    rows: &mut Vec<TreeRow>,
+) {
+    let children: Vec<&Project> = projects
+        .iter()
+        .filter(|p| p.parent_id.as_deref() == Some(project.id.as_str()))
+        .collect();
+    rows.push(TreeRow {
+        project: project.clone(),
+        depth,
+        has_children: !children.is_empty(),
+        group: Vec::new(),
+    });
+    if project.collapsed && !expand_all {
+        return;
+    }
+    for child in children {
+        push_tree_rows(projects, child, depth + 1, expand_all, rows);
+    }
+}
+
+/// Whether `ancestor_id` is `project_id` itself or one of its parents.
+fn is_ancestor(projects: &[Project], ancestor_id: &str, project_id: &str) -> bool {
+    let mut current = Some(project_id.to_string());
+    // Bounded walk, so a corrupted DB with a cycle can't hang the UI.
+    for _ in 0..=projects.len() {
+        match current {
+            Some(id) if id == ancestor_id => return true,
+            Some(id) => {
+                current = projects
+                    .iter()
+                    .find(|p| p.id == id)
+                    .and_then(|p| p.parent_id.clone());
+            }
+            None => return false,
+        }
+    }
+    false
+}
+
+fn add_project_to_db(project_name: String, directory: String) -> Result<Vec<Project>, Error> {
+    let db_content = fs::read_to_string(get_db_path()?)?;
+    let mut parsed: Vec<Project> = serde_json::from_str(&db_content)?;
+
+    let new_project = new_project(&parsed, project_name, directory);
+    parsed.push(new_project.clone());
+    write_db(&parsed)?;
+    hooks::run(hooks::Event::Add, &new_project);
+    Ok(parsed)
+}
+
+/// Adds a project for each of `directories` that isn't one yet, named after the
+/// directory, in a single write. Returns how many were added.
+fn add_directories(mut directories: Vec<PathBuf>) -> Result<usize, Error> {
+    // Sorted, parents come before the projects inside them and become their parents.
+    directories.sort();
+    directories.dedup();
+    let mut projects = read_db()?;
+    let known = projects.len();
+    for directory in directories {
+        let directory = directory.to_string_lossy().to_string();
+        if projects.iter().any(|p| p.directory == directory) {
+            continue;
+        }
+        let name = Path::new(&directory)
+            .file_name()
+            .map_or_else(|| directory.clone(), |name| name.to_string_lossy().to_string());
+        let project = new_project(&projects, name, directory);
+        projects.push(project);
+    }
+    let added = projects.len() - known;
+    if added > 0 {
+        write_db(&projects)?;
+    }
+    for project in &projects[known..] {
+        hooks::run(hooks::Event::Add, project);
+    }
+    Ok(added)
+}
+
+/// A project for `directory` to be added after `projects`.
+fn new_project(projects: &[Project], project_name: String, directory: String) -> Project {
+    // Projects added inside an existing project's directory become its sub-projects.
+    let parent_id = projects
+        .iter()
+        .filter(|p| Path::new(&directory).starts_with(&p.directory) && p.directory != directory)
+        .max_by_key(|p| p.directory.len())
+        .map(|p| p.id.clone());
+
+    Project {
+        id: Uuid::new_v4().to_string(),
+        name: project_name,
+        repo_url: git::detect_remote_url(&directory),
+        alias: None,
+        parent_id,
+        collapsed: false,
+        order: projects.iter().map(|p| p.order + 1).max().unwrap_or(0),
+        due_date: None,
+        todos: Vec::new(),
+        time_entries: Vec::new(),
+        links: Vec::new(),
+        tags: Vec::new(),
+        workspace: None,
+        last_opened_at: None,
+        open_count: 0,
+        notes: String::new(),
+        disk_usage: None,
+        tasks: BTreeMap::new(),
+        env: BTreeMap::new(),
+        open_command: None,
+        directory,
+        created_at: Utc::now(),
+        status: Status::default(),
+        priority: Priority::default(),
+    }
+}
+
+/// Everything needed to put a removed project back the way it was.
+struct DeletedProject {
+    project: Project,
+    /// Position of the project in the DB.
+    index: usize,
+    /// Sub-projects that were moved up to the removed project's parent.
+    children: Vec<String>,
+    /// Links other projects had to the removed one, by the ID of the linking project.
+    links: Vec<(String, ProjectLink)>,
+}
+
+fn remove_project(id: &str) -> Result<Option<DeletedProject>, Error> {
+    let mut parsed = read_db()?;
+    let Some(index) = parsed.iter().position(|p| p.id == id) else {
+        return Ok(None);
+    };
+    let project = parsed.remove(index);
+    let mut children = Vec::new();
+    let mut links = Vec::new();
+    // Sub-projects move up to the removed project's parent.
+    for p in parsed.iter_mut() {
+        if p.parent_id.as_deref() == Some(project.id.as_str()) {
+            p.parent_id = project.parent_id.clone();
+            children.push(p.id.clone());
+        }
+        for link in p.links.iter().filter(|link| link.target_id == project.id) {
+            links.push((p.id.clone(), link.clone()));
+        }
+        p.links.retain(|link| link.target_id != project.id);
+    }
+    write_db(&parsed)?;
+    hooks::run(hooks::Event::Remove, &project);
+    Ok(Some(DeletedProject {
+        project,
+        index,
+        children,
+        links,
+    }))
+}
+
+/// Undoes `remove_project`.
+fn restore_project(deleted: DeletedProject) -> Result<(), Error> {
+    let mut parsed = read_db()?;
+    for p in parsed.iter_mut() {
+        if deleted.children.contains(&p.id) {
+            p.parent_id = Some(deleted.project.id.clone());
+        }
+        for (_, link) in deleted.links.iter().filter(|(source_id, _)| *source_id == p.id) {
+            p.links.push(link.clone());
+        }
+    }
+    let index = deleted.index.min(parsed.len());
+    parsed.insert(index, deleted.project);
+    write_db(&parsed)
+}
+
+/// Finds a project by alias, then ID, then exact name, then an unambiguous ID prefix
+/// of at least four characters (so short IDs work).
+fn find_project<'a>(projects: &'a [Project], query: &str) -> Option<&'a Project> {
+    projects
+        .iter()
+        .find(|p| p.alias.as_deref() == Some(query))
+        .or_else(|| projects.iter().find(|p| p.id == query))
+        .or_else(|| projects.iter().find(|p| p.name == query))
+        .or_else(|| {
+            if query.len() < 4 {
+                return None;
+            }
+            let mut matches = projects.iter().filter(|p| p.id.starts_with(query));
+            match (matches.next(), matches.next()) {
+                (Some(project), None) => Some(project),
+                _ => None,
+            }
+        })
+}
+
+fn selected_project(project_list_state: &ListState, view: &ListView) -> Result<Option<Project>, Error> {
+    match project_list_state.selected() {
+        Some(selected) => Ok(visible_projects(view)?.into_iter().nth(selected)),
+        None => Ok(None),
+    }
+}
+
+/// Applies `update` to the project at the given index of the visible list and saves the DB.
+fn update_project_at_index(
+    project_list_state: &ListState,
+    view: &ListView,
+    update: impl FnOnce(&mut Project),
+) -> Result<(), Error> {
+    if let Some(project) = selected_project(project_list_state, view)? {
+        let mut parsed = read_db()?;
+        if let Some(p) = parsed.iter_mut().find(|p| p.id == project.id) {
+            update(p);
+        }
+        write_db(&parsed)?;
+    }
+    Ok(())
+}
+
+fn count_projects(amount: usize) -> String {
+    match amount {
+        1 => "1 project".to_string(),
+        n => format!("{} projects", n),
+    }
+}
+
+/// Records that a project was just opened, for sorting by last use.
+fn mark_opened(id: &str) -> Result<(), Error> {
+    if let Some(project) = read_db()?.iter().find(|p| p.id == id) {
+        hooks::run(hooks::Event::Open, project);
+    }
+    // Only bookkeeping, which doesn't keep a read-only DB from being browsed.
+    if is_read_only() {
+        return Ok(());
+    }
+    update_projects(&[id.to_string()], |p| {
+        p.last_opened_at = Some(Utc::now());
+        p.open_count += 1;
+    })
+}
+
+/// Applies `update` to every project whose ID is in `ids` and saves the DB.
+fn update_projects(ids: &[String], mut update: impl FnMut(&mut Project)) -> Result<(), Error> {
+    let mut parsed = read_db()?;
+    for p in parsed.iter_mut().filter(|p| ids.contains(&p.id)) {
+        update(p);
+    }
+    write_db(&parsed)
+}
+
+/// Saves the sizes measured by `usage::UsageScanner` to every project in each directory.
+fn store_disk_usage(measured: &[(String, u64)]) -> Result<(), Error> {
+    if is_read_only() {
+        return Ok(());
+    }
+    let mut parsed = read_db()?;
+    let measured_at = Utc::now();
+    for project in parsed.iter_mut() {
+        if let Some(&(_, bytes)) = measured.iter().find(|(dir, _)| *dir == project.directory) {
+            project.disk_usage = Some(DiskUsage { bytes, measured_at });
+        }
+    }
+    write_db(&parsed)
+}
+
+/// The projects a bulk action applies to: the marked ones, or the selected one if none is marked.
+fn bulk_targets(marked: &[String], project_list_state: &ListState, view: &ListView) -> Result<Vec<String>, Error> {
+    if !marked.is_empty() {
+        return Ok(marked.to_vec());
+    }
+    Ok(selected_project(project_list_state, view)?.map(|p| p.id).into_iter().collect())
+}
+
+/// Adds the tags in `input` to the projects, or removes those written as `-tag`.
+fn edit_tags(ids: &[String], input: &str) -> Result<(), Error> {
+    update_projects(ids, |p| {
+        for word in input.split_whitespace() {
+            match word.strip_prefix('-') {
+                Some(tag) => p.tags.retain(|t| t != tag),
+                None if !p.tags.iter().any(|t| t == word) => p.tags.push(word.to_string()),
+                None => {}
+            }
+        }
+    })
+}
+
+/// Opens `target` with the platform's default handler without blocking the UI: a URL in
+/// the browser, a directory in the file manager.
+fn open_externally(target: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+
+    command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
+/// Puts `text` on the clipboard with the OSC 52 escape sequence, which the terminal
+/// handles, so it also works over SSH and without a clipboard tool installed. It goes
+/// straight to the terminal, as the output of the picker is captured by the shell.
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    let mut terminal: Box<dyn Write> = match fs::OpenOptions::new().write(true).open("/dev/tty") {
+        Ok(tty) => Box::new(tty),
+        // Without a /dev/tty, like on Windows, stderr is the terminal unless redirected,
+        // in the picker as well.
+        Err(_) => Box::new(io::stderr()),
+    };
+    write!(terminal, "\x1b]52;c;{}\x07", base64(text.as_bytes()))?;
+    terminal.flush()
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn set_alias_at_index(project_list_state: &ListState, view: &ListView, alias: &str) -> Result<(), Error> {
+    let Some(project) = selected_project(project_list_state, view)? else {
+        return Ok(());
+    };
+    let alias = alias.trim();
+    let alias = if alias.is_empty() {
+        None
+    } else {
+        if !alias.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            return Err(Error::InvalidAlias(alias.to_string()));
+        }
+        let taken = read_db()?
+            .iter()
+            .any(|p| p.id != project.id && p.alias.as_deref() == Some(alias));
+        if taken {
+            return Err(Error::DuplicateAlias(alias.to_string()));
+        }
+        Some(alias.to_string())
+    };
+
+    update_project_at_index(project_list_state, view, |p| p.alias = alias)
+}
+
+/// Selects the visible project matching `query` by alias, then by name prefix.
+fn jump_to_project(project_list_state: &mut ListState, view: &ListView, query: &str) -> Result<(), Error> {
+    let projects = visible_projects(view)?;
+    let query = query.trim();
+    let position = projects
+        .iter()
+        .position(|p| p.alias.as_deref() == Some(query))
+        .or_else(|| {
+            let query = query.to_lowercase();
+            projects
+                .iter()
+                .position(|p| p.name.to_lowercase().starts_with(&query))
+        });
+
+    match position {
+        Some(index) => {
+            project_list_state.select(Some(index));
+            Ok(())
+        }
+        None => Err(Error::ProjectNotFound(query.to_string())),
+    }
+}
+
+fn set_parent_at_index(project_list_state: &ListState, view: &ListView, query: &str) -> Result<(), Error> {
+    let Some(project) = selected_project(project_list_state, view)? else {
+        return Ok(());
+    };
+    let query = query.trim();
+    let parent_id = if query.is_empty() {
+        None
+    } else {
+        let projects = read_db()?;
+        let parent = find_project(&projects, query)
+            .ok_or_else(|| Error::ProjectNotFound(query.to_string()))?;
+        if is_ancestor(&projects, &project.id, &parent.id) {
+            return Err(Error::ParentCycle(project.name));
+        }
+        Some(parent.id.clone())
+    };
+
+    update_project_at_index(project_list_state, view, |p| p.parent_id = parent_id)
+}
+
+/// Swaps the selected project with its previous (`offset < 0`) or next sibling in the
+/// manual order and switches the list to the manual sort so the move is visible.
+fn move_project_at_index(
+    project_list_state: &mut ListState,
+    view: &mut ListView,
+    offset: isize,
+) -> Result<(), Error> {
+    let Some(project) = selected_project(project_list_state, view)? else {
+        return Ok(());
+    };
+    view.sort_mode = SortMode::Manual;
+
+    let mut parsed = read_db()?;
+    let mut ordered: Vec<usize> = (0..parsed.len()).collect();
+    ordered.sort_by_key(|&i| parsed[i].order);
+    for (position, &i) in ordered.iter().enumerate() {
+        parsed[i].order = position;
+    }
+
+    let visible: Vec<String> = visible_projects(view)?.into_iter().map(|p| p.id).collect();
+    let siblings: Vec<usize> = ordered
+        .into_iter()
+        .filter(|&i| parsed[i].parent_id == project.parent_id && visible.contains(&parsed[i].id))
+        .collect();
+    let Some(position) = siblings.iter().position(|&i| parsed[i].id == project.id) else {
+        return Ok(());
+    };
+    let Some(&neighbour) = position
+        .checked_add_signed(offset)
+        .and_then(|target| siblings.get(target))
+    else {
+        return Ok(());
+    };
+
+    let current = siblings[position];
+    let order = parsed[current].order;
+    parsed[current].order = parsed[neighbour].order;
+    parsed[neighbour].order = order;
+    write_db(&parsed)?;
+
+    let index = visible_projects(view)?.iter().position(|p| p.id == project.id);
+    project_list_state.select(index.or(Some(0)));
+    Ok(())
+}
+
+fn set_due_date_at_index(project_list_state: &ListState, view: &ListView, input: &str) -> Result<(), Error> {
+    let input = input.trim();
+    let due_date = if input.is_empty() {
+        None
+    } else {
+        let date = NaiveDate::parse_from_str(input, "%Y-%m-%d")
+            .map_err(|_| Error::InvalidDate(input.to_string()))?;
+        Some(date)
+    };
+
+    update_project_at_index(project_list_state, view, |p| p.due_date = due_date)
+}
+
+fn add_todo_at_index(project_list_state: &ListState, view: &ListView, text: &str) -> Result<(), Error> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(());
+    }
+    update_project_at_index(project_list_state, view, |p| {
+        p.todos.push(TodoItem {
+            text: text.to_string(),
+            done: false,
+        })
+    })
+}
+
+/// Starts the timer of the selected project, stopping any other running timer,
+/// or stops it if it's already running.
+fn toggle_timer_at_index(project_list_state: &ListState, view: &ListView) -> Result<(), Error> {
+    let Some(project) = selected_project(project_list_state, view)? else {
+        return Ok(());
+    };
+    let now = Utc::now();
+    let mut parsed = read_db()?;
+    for p in parsed.iter_mut() {
+        let was_running = p.is_timer_running();
+        for entry in p.time_entries.iter_mut().filter(|entry| entry.end.is_none()) {
+            entry.end = Some(now);
+        }
+        if p.id == project.id && !was_running {
+            p.time_entries.push(TimeEntry { start: now, end: None });
+        }
+    }
+    write_db(&parsed)
+}
+
+/// Links of `project` in both directions, labelled from its point of view.
+fn project_links(project: &Project) -> Result<Vec<(&'static str, Project)>, Error> {
+    let projects = read_db()?;
+    let mut links = Vec::new();
+    for link in &project.links {
+        if let Some(target) = projects.iter().find(|p| p.id == link.target_id) {
+            let label = match link.kind {
+                LinkKind::DependsOn => "depends on",
+                LinkKind::RelatedTo => "related to",
+            };
+            links.push((label, target.clone()));
+        }
+    }
+    for other in &projects {
+        for link in other.links.iter().filter(|link| link.target_id == project.id) {
+            let label = match link.kind {
+                LinkKind::DependsOn => "needed by",
+                LinkKind::RelatedTo => "related to",
+            };
+            links.push((label, other.clone()));
+        }
+    }
+    Ok(links)
+}
+
+/// Links the selected project to the one matching `query`, or removes the link if it exists.
+fn toggle_link_at_index(
+    project_list_state: &ListState,
+    view: &ListView,
+    kind: LinkKind,
+    query: &str,
+) -> Result<(), Error> {
+    let Some(project) = selected_project(project_list_state, view)? else {
+        return Ok(());
+    };
+    let projects = read_db()?;
+    let target = find_project(&projects, query.trim())
+        .ok_or_else(|| Error::ProjectNotFound(query.trim().to_string()))?;
+    if target.id == project.id {
+        return Ok(());
+    }
+    let target_id = target.id.clone();
+
+    update_project_at_index(project_list_state, view, |p| {
+        let before = p.links.len();
+        p.links.retain(|link| !(link.kind == kind && link.target_id == target_id));
+        if p.links.len() == before {
+            p.links.push(ProjectLink { kind, target_id });
+        }
+    })
+}
+
+/// Selects the project with the given ID, clearing the filters and the workspace and
+/// expanding its parents and section if that's what it takes to make it visible. False
+/// if it still isn't, when it's gone.
+fn select_project_by_id(project_list_state: &mut ListState, view: &mut ListView, id: &str) -> Result<bool, Error> {
+    if let Some(index) = visible_projects(view)?.iter().position(|p| p.id == id) {
+        project_list_state.select(Some(index));
+        return Ok(true);
+    }
+
+    view.status_filter = None;
+    view.workspace = None;
+    view.search.clear();
+    let mut parsed = read_db()?;
+    let ancestors: Vec<String> = parsed
+        .iter()
+        .filter(|p| p.id != id && is_ancestor(&parsed, &p.id, id))
+        .map(|p| p.id.clone())
+        .collect();
+    if is_read_only() {
+        view.expanded.extend(ancestors);
+    } else {
+        for p in parsed.iter_mut().filter(|p| ancestors.contains(&p.id)) {
+            p.collapsed = false;
+        }
+        write_db(&parsed)?;
+    }
+    if let Some(row) = project_tree(view)?.into_iter().find(|row| row.project.id == id) {
+        let keys: Vec<String> = (1..=row.group.len()).map(|depth| view.group_by.key(&row.group[..depth])).collect();
+        view.collapsed_groups.retain(|key| !keys.contains(key));
+    }
+
+    let index = visible_projects(view)?.iter().position(|p| p.id == id);
+    project_list_state.select(index.or(Some(0)));
+    Ok(index.is_some())
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tui::{
+    style::{Modifier, Style},
+    text::{Span, Spans},
+};
+
+use crate::theme::Theme;
+
+/// The project's README, if there is one: `README.md` or any other file named `readme*`.
+pub fn find_readme(directory: &Path) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().to_lowercase().starts_with("readme"))
+        })
+        .collect();
+    // Prefer the markdown one when there are several.
+    candidates.sort_by_key(|path| !path.to_string_lossy().to_lowercase().ends_with(".md"));
+    candidates.into_iter().next()
+}
+
+/// Styles the basics of markdown: headers, list items, code blocks, inline code and bold text.
+pub fn render(text: &str, theme: &Theme) -> Vec<Spans<'static>> {
+    let code = Style::default().fg(theme.accent);
+    let mut in_code_block = false;
+    let mut lines = Vec::new();
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code_block = !in_code_block;
+            continue;
+        }
+        if in_code_block {
+            lines.push(Spans::from(Span::styled(format!("  {}", line), code)));
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let header_level = trimmed.chars().take_while(|c| *c == '#').count();
+        if (1..=6).contains(&header_level) && trimmed[header_level..].starts_with(' ') {
+            let mut style = Style::default().fg(theme.title).add_modifier(Modifier::BOLD);
+            if header_level == 1 {
+                style = style.add_modifier(Modifier::UNDERLINED);
+            }
+            lines.push(Spans::from(Span::styled(trimmed[header_level..].trim().to_string(), style)));
+            continue;
+        }
+
+        let indent = &line[..line.len() - trimmed.len()];
+        let item = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .or_else(|| trimmed.strip_prefix("+ "));
+        let mut spans = Vec::new();
+        let rest = match item {
+            Some(rest) => {
+                spans.push(Span::styled(format!("{}• ", indent), Style::default().fg(theme.accent)));
+                rest
+            }
+            None => line,
+        };
+        spans.extend(inline(rest, code));
+        lines.push(Spans::from(spans));
+    }
+    lines
+}
+
+/// Splits a line on `` ` `` and `**` markers, styling what's between them.
+fn inline(text: &str, code: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut in_code = false;
+    let mut bold = false;
+    let mut chars = text.chars().peekable();
+
+    let style = |in_code: bool, bold: bool| {
+        if in_code {
+            code
+        } else if bold {
+            Style::default().add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        let toggles_bold = !in_code && c == '*' && chars.peek() == Some(&'*');
+        if c == '`' || toggles_bold {
+            if !current.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut current), style(in_code, bold)));
+            }
+            if toggles_bold {
+                chars.next();
+                bold = !bold;
+            } else {
+                in_code = !in_code;
+            }
+            continue;
+        }
+        current.push(c);
+    }
+    if !current.is_empty() {
+        spans.push(Span::styled(current, style(in_code, bold)));
+    }
+    spans
+}
@@ -0,0 +1,91 @@
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use chrono::Local;
+
+use crate::{config, count_projects, state, Project, Status};
+
+/// How long a task has to run before it's worth a notification when it's done. Quicker
+/// ones finish while the user is still looking.
+pub const LONG_TASK: Duration = Duration::from_secs(10);
+
+/// Whether the config asks for desktop notifications.
+pub fn enabled() -> bool {
+    config::load().is_ok_and(|config| config.notifications)
+}
+
+/// Shows a desktop notification with notify-send, or osascript on macOS, in the
+/// background. Nothing happens where neither is available, a notification is only ever
+/// a nicety.
+pub fn send(summary: &str, body: &str) {
+    let Some(mut command) = notify_command(summary, body) else {
+        return;
+    };
+    let started = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Ok(mut child) = started {
+        thread::spawn(move || child.wait());
+    }
+}
+
+/// Like `send`, if the config asks for notifications and the task took `elapsed`, which
+/// is at least `LONG_TASK`.
+pub fn task_done(elapsed: Duration, summary: &str, body: &str) {
+    if elapsed >= LONG_TASK && enabled() {
+        send(summary, body);
+    }
+}
+
+/// Notifies about the open projects due today or overdue, once a day.
+pub fn remind_due(projects: &[Project]) {
+    let today = Local::now().date_naive();
+    let mut saved_state = state::load();
+    if saved_state.reminded_on == Some(today) {
+        return;
+    }
+    let mut due: Vec<&Project> = projects
+        .iter()
+        .filter(|p| matches!(p.status, Status::Active | Status::OnHold))
+        .filter(|p| p.due_date.is_some_and(|due| due <= today))
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+    due.sort_by_key(|p| p.due_date);
+    let body: Vec<String> = due
+        .iter()
+        .map(|p| match p.due_date {
+            Some(due) if due < today => format!("{} (overdue)", p.name),
+            _ => format!("{} (today)", p.name),
+        })
+        .collect();
+    send(&format!("{} due", count_projects(due.len())), &body.join(", "));
+    saved_state.reminded_on = Some(today);
+    let _ = state::save(&saved_state);
+}
+
+#[cfg(target_os = "macos")]
+fn notify_command(summary: &str, body: &str) -> Option<Command> {
+    // AppleScript strings take the same escapes as JSON ones.
+    let quote = |text: &str| serde_json::to_string(text).unwrap_or_default();
+    let script = format!("display notification {} with title \"whisk\" subtitle {}", quote(body), quote(summary));
+    let mut command = Command::new("osascript");
+    command.args(["-e", &script]);
+    Some(command)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn notify_command(summary: &str, body: &str) -> Option<Command> {
+    let mut command = Command::new("notify-send");
+    command.args(["--app-name", "whisk", summary, body]);
+    Some(command)
+}
+
+#[cfg(not(unix))]
+fn notify_command(_summary: &str, _body: &str) -> Option<Command> {
+    None
+}
@@ -0,0 +1,165 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::search::SKIPPED_DIRECTORIES;
+
+/// The pickers the `picker` setting of the config can name.
+pub const NAMES: [&str; 4] = ["built-in", "xplr", "fzf", "broot"];
+
+/// How deep below the start directory fzf is offered directories.
+const FZF_DEPTH: usize = 4;
+
+/// An external tool for choosing the directory of a new project. The built-in browser
+/// isn't one, it's drawn by the UI itself.
+pub trait Picker {
+    fn name(&self) -> &'static str;
+
+    /// Whether the tool can be run, false when it isn't installed or built in.
+    fn is_available(&self) -> bool;
+
+    /// Lets the user choose a directory, starting at `start`. It takes over the terminal,
+    /// so the UI has to hand it over first. `None` if nothing was chosen.
+    fn pick(&self, start: &Path) -> Result<Option<PathBuf>, String>;
+}
+
+/// The external picker called `name`, `None` for the built-in browser. The error is what
+/// to tell the user when the name is unknown.
+pub fn find(name: &str) -> Result<Option<Box<dyn Picker>>, String> {
+    match name {
+        "built-in" | "" => Ok(None),
+        "xplr" => Ok(Some(Box::new(Xplr))),
+        "fzf" => Ok(Some(Box::new(Fzf))),
+        "broot" => Ok(Some(Box::new(Broot))),
+        other => Err(format!("unknown picker '{}', expected one of {}", other, NAMES.join(", "))),
+    }
+}
+
+/// xplr, which is built in as a library with the `picker-xplr` feature.
+pub struct Xplr;
+
+impl Picker for Xplr {
+    fn name(&self) -> &'static str {
+        "xplr"
+    }
+
+    fn is_available(&self) -> bool {
+        cfg!(feature = "picker-xplr")
+    }
+
+    #[cfg(not(feature = "picker-xplr"))]
+    fn pick(&self, _start: &Path) -> Result<Option<PathBuf>, String> {
+        Err("whisk was built without the picker-xplr feature".to_string())
+    }
+
+    #[cfg(feature = "picker-xplr")]
+    fn pick(&self, start: &Path) -> Result<Option<PathBuf>, String> {
+        let cli = xplr::cli::Cli { paths: vec![start.to_path_buf()], ..Default::default() };
+        match xplr::runner::from_cli(cli).and_then(|runner| runner.run()) {
+            Ok(output) => Ok(output.as_deref().and_then(first_line).map(directory_of)),
+            // Quitting xplr without a choice is an error without a message.
+            Err(err) if err.to_string().is_empty() => Ok(None),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
+
+/// fzf, offered the directories below the start directory.
+pub struct Fzf;
+
+impl Picker for Fzf {
+    fn name(&self) -> &'static str {
+        "fzf"
+    }
+
+    fn is_available(&self) -> bool {
+        is_installed("fzf")
+    }
+
+    fn pick(&self, start: &Path) -> Result<Option<PathBuf>, String> {
+        let mut child = Command::new("fzf")
+            .args(["--prompt", "directory> "])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|err| format!("can't run fzf: {}", err))?;
+        let directories: Vec<String> = directories_below(start, FZF_DEPTH)
+            .iter()
+            .map(|directory| directory.to_string_lossy().to_string())
+            .collect();
+        if let Some(mut stdin) = child.stdin.take() {
+            // fzf closes its input once something is chosen, which isn't an error.
+            let _ = stdin.write_all(directories.join("\n").as_bytes());
+        }
+        let output = child.wait_with_output().map_err(|err| format!("fzf failed: {}", err))?;
+        Ok(first_line(&String::from_utf8_lossy(&output.stdout)).map(PathBuf::from))
+    }
+}
+
+/// broot, which prints the directory chosen with `:print_path`.
+pub struct Broot;
+
+impl Picker for Broot {
+    fn name(&self) -> &'static str {
+        "broot"
+    }
+
+    fn is_available(&self) -> bool {
+        is_installed("broot")
+    }
+
+    fn pick(&self, start: &Path) -> Result<Option<PathBuf>, String> {
+        let output = Command::new("broot")
+            .arg("--only-folders")
+            .arg(start)
+            .stdout(Stdio::piped())
+            .output()
+            .map_err(|err| format!("can't run broot: {}", err))?;
+        Ok(first_line(&String::from_utf8_lossy(&output.stdout)).map(directory_of))
+    }
+}
+
+fn first_line(output: &str) -> Option<&str> {
+    output.lines().map(str::trim).find(|line| !line.is_empty())
+}
+
+/// The path itself if it's a directory, otherwise the one it's in.
+fn directory_of(path: &str) -> PathBuf {
+    let path = PathBuf::from(path);
+    match path.parent() {
+        Some(parent) if !path.is_dir() => parent.to_path_buf(),
+        _ => path,
+    }
+}
+
+/// Whether `program` is an executable file in `$PATH`.
+fn is_installed(program: &str) -> bool {
+    env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
+
+/// `start` and the directories up to `depth` levels below it, leaving out hidden and
+/// build directories, sorted.
+fn directories_below(start: &Path, depth: usize) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut pending = vec![(start.to_path_buf(), 0)];
+    while let Some((dir, level)) = pending.pop() {
+        found.push(dir.clone());
+        if level == depth {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+            if is_dir && !name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                pending.push((entry.path(), level + 1));
+            }
+        }
+    }
+    found.sort();
+    found
+}
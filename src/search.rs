@@ -0,0 +1,101 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// Directories with build output or dependencies rather than the project's own files.
+pub const SKIPPED_DIRECTORIES: [&str; 6] = ["target", "node_modules", "vendor", "dist", "build", "__pycache__"];
+
+/// Files larger than this are skipped, they're hardly ever source.
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// A line matching the pattern.
+pub struct Match {
+    /// Relative to the searched directory.
+    pub path: PathBuf,
+    /// Counted from 1.
+    pub line_number: usize,
+    pub line: String,
+}
+
+/// The lines matching `pattern` in the text files below `root`, in path order. Hidden
+/// files and directories and those in `SKIPPED_DIRECTORIES` are left out. With `globs`,
+/// only files whose names match one of them are searched.
+pub fn search(root: &Path, pattern: &Regex, globs: &[String]) -> Vec<Match> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if name.starts_with('.') {
+                continue;
+            }
+            if file_type.is_dir() {
+                if !SKIPPED_DIRECTORIES.contains(&name.as_str()) {
+                    pending.push(entry.path());
+                }
+            } else if file_type.is_file() && (globs.is_empty() || globs.iter().any(|glob| glob_match(glob, &name))) {
+                files.push(entry.path());
+            }
+        }
+    }
+    files.sort();
+
+    let mut matches = Vec::new();
+    for file in files {
+        if fs::metadata(&file).map_or(true, |metadata| metadata.len() > MAX_FILE_SIZE) {
+            continue;
+        }
+        let Ok(content) = fs::read(&file) else {
+            continue;
+        };
+        // Like grep, a NUL byte means it's binary.
+        if content.contains(&0) {
+            continue;
+        }
+        let content = String::from_utf8_lossy(&content);
+        let path = file.strip_prefix(root).unwrap_or(&file).to_path_buf();
+        for (i, line) in content.lines().enumerate() {
+            if pattern.is_match(line) {
+                matches.push(Match { path: path.clone(), line_number: i + 1, line: line.to_string() });
+            }
+        }
+    }
+    matches
+}
+
+/// Whether `name` matches `glob`, where `*` stands for any characters and `?` for one.
+fn glob_match(glob: &str, name: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Where to resume after a mismatch: the last `*` and the name position it covers up to.
+    let (mut g, mut n) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                star = Some((g, n));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match star {
+                Some((star_g, star_n)) => {
+                    g = star_g + 1;
+                    n = star_n + 1;
+                    star = Some((star_g, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
@@ -0,0 +1,50 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::{data_dir, Error, GroupBy, MenuItem, SortMode, Status};
+
+/// UI choices remembered between runs, kept in `~/.config/whisk/state.json` (see `data_dir`).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct UiState {
+    pub sort_mode: SortMode,
+    pub group_by: GroupBy,
+    pub collapsed_groups: Vec<String>,
+    /// Where the last run was left off, restored unless whisk is started with `--fresh`.
+    pub session: Session,
+    /// The day due projects were last notified about, so it's once a day.
+    pub reminded_on: Option<NaiveDate>,
+}
+
+/// The tab, filters and selected project of the UI.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Session {
+    pub tab: MenuItem,
+    pub workspace: Option<String>,
+    pub status_filter: Option<Status>,
+    pub search: String,
+    /// The ID of the project.
+    pub selected: Option<String>,
+}
+
+fn state_path() -> PathBuf {
+    data_dir().join("state.json")
+}
+
+/// Loads the saved state. It's only a convenience, so a missing or broken file
+/// just means starting from the defaults.
+pub fn load() -> UiState {
+    fs::read_to_string(state_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(state: &UiState) -> Result<(), Error> {
+    fs::write(state_path(), serde_json::to_vec(state)?)?;
+    Ok(())
+}
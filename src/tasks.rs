@@ -0,0 +1,115 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const SPINNER: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Lets a running task notice that it was cancelled. Tasks check it between steps.
+#[derive(Clone, Default)]
+pub struct CancelFlag(Arc<AtomicBool>);
+
+impl CancelFlag {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// What a task reports when it's done: a message for the user, or what went wrong.
+pub type Outcome = Result<String, String>;
+
+pub struct Task {
+    id: usize,
+    pub label: String,
+    pub started_at: Instant,
+    cancel: CancelFlag,
+}
+
+impl Task {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.is_cancelled()
+    }
+}
+
+/// A finished task, as reported by `TaskRunner::finished`.
+pub struct Finished {
+    pub label: String,
+    pub outcome: Outcome,
+    pub cancelled: bool,
+    /// How long it ran.
+    pub elapsed: Duration,
+}
+
+/// Runs long operations on their own threads so the UI stays responsive.
+pub struct TaskRunner {
+    pub running: Vec<Task>,
+    next_id: usize,
+    sender: Sender<(usize, Outcome)>,
+    receiver: Receiver<(usize, Outcome)>,
+}
+
+impl TaskRunner {
+    pub fn new() -> TaskRunner {
+        let (sender, receiver) = mpsc::channel();
+        TaskRunner {
+            running: Vec::new(),
+            next_id: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    pub fn spawn(&mut self, label: impl Into<String>, work: impl FnOnce(CancelFlag) -> Outcome + Send + 'static) {
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancel = CancelFlag::default();
+        let flag = cancel.clone();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let outcome = work(flag);
+            // The UI may be gone already, then nobody's waiting for the result.
+            let _ = sender.send((id, outcome));
+        });
+        self.running.push(Task {
+            id,
+            label: label.into(),
+            started_at: Instant::now(),
+            cancel,
+        });
+    }
+
+    /// Asks the task at `index` of `running` to stop. It's reported as finished once it does.
+    pub fn cancel(&mut self, index: usize) {
+        if let Some(task) = self.running.get(index) {
+            task.cancel.cancel();
+        }
+    }
+
+    /// The tasks that finished since the last call.
+    pub fn finished(&mut self) -> Vec<Finished> {
+        let mut finished = Vec::new();
+        for (id, outcome) in self.receiver.try_iter() {
+            if let Some(index) = self.running.iter().position(|task| task.id == id) {
+                let task = self.running.remove(index);
+                finished.push(Finished {
+                    cancelled: task.is_cancelled(),
+                    elapsed: task.started_at.elapsed(),
+                    label: task.label,
+                    outcome,
+                });
+            }
+        }
+        finished
+    }
+
+    /// The current frame of the spinner shown while tasks are running.
+    pub fn spinner(&self) -> &'static str {
+        let started_at = self.running.first().map_or_else(Instant::now, |task| task.started_at);
+        SPINNER[(started_at.elapsed().as_millis() / 100) as usize % SPINNER.len()]
+    }
+}
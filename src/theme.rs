@@ -0,0 +1,361 @@
+use serde::{Deserialize, Serialize};
+use tui::style::{Color, Modifier, Style};
+
+use crate::{Error, Priority, Status};
+
+/// Every color used by the UI, by role.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Theme {
+    pub text: Color,
+    pub border: Color,
+    pub accent: Color,
+    pub selection_fg: Color,
+    pub selection_bg: Color,
+    pub muted: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub success: Color,
+    pub title: Color,
+    pub search_match: Color,
+    pub timer: Color,
+    pub todo_count: Color,
+    pub status_active: Color,
+    pub status_on_hold: Color,
+    pub status_done: Color,
+    pub status_abandoned: Color,
+    pub status_archived: Color,
+    pub priority_high: Color,
+    pub priority_medium: Color,
+    pub priority_low: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            text: Color::White,
+            border: Color::White,
+            accent: Color::Yellow,
+            selection_fg: Color::Black,
+            selection_bg: Color::Yellow,
+            muted: Color::DarkGray,
+            error: Color::Red,
+            warning: Color::Yellow,
+            success: Color::Green,
+            title: Color::LightBlue,
+            search_match: Color::LightYellow,
+            timer: Color::LightRed,
+            todo_count: Color::Cyan,
+            status_active: Color::Green,
+            status_on_hold: Color::Yellow,
+            status_done: Color::Blue,
+            status_abandoned: Color::DarkGray,
+            status_archived: Color::DarkGray,
+            priority_high: Color::Red,
+            priority_medium: Color::White,
+            priority_low: Color::DarkGray,
+        }
+    }
+}
+
+impl Theme {
+    /// Names of the built-in themes, in the order 'C' cycles through them.
+    pub const NAMES: [&'static str; 6] = [
+        "default",
+        "solarized",
+        "gruvbox",
+        "monochrome",
+        "high-contrast",
+        "deuteranopia",
+    ];
+
+    pub fn builtin(name: &str) -> Option<Theme> {
+        match name {
+            "default" => Some(Theme::default()),
+            "solarized" => Some(Theme {
+                text: Color::Rgb(0x83, 0x94, 0x96),
+                border: Color::Rgb(0x58, 0x6e, 0x75),
+                accent: Color::Rgb(0xb5, 0x89, 0x00),
+                selection_fg: Color::Rgb(0xfd, 0xf6, 0xe3),
+                selection_bg: Color::Rgb(0x26, 0x8b, 0xd2),
+                muted: Color::Rgb(0x58, 0x6e, 0x75),
+                error: Color::Rgb(0xdc, 0x32, 0x2f),
+                warning: Color::Rgb(0xb5, 0x89, 0x00),
+                success: Color::Rgb(0x85, 0x99, 0x00),
+                title: Color::Rgb(0x26, 0x8b, 0xd2),
+                search_match: Color::Rgb(0xcb, 0x4b, 0x16),
+                timer: Color::Rgb(0xd3, 0x36, 0x82),
+                todo_count: Color::Rgb(0x2a, 0xa1, 0x98),
+                status_active: Color::Rgb(0x85, 0x99, 0x00),
+                status_on_hold: Color::Rgb(0xb5, 0x89, 0x00),
+                status_done: Color::Rgb(0x26, 0x8b, 0xd2),
+                status_abandoned: Color::Rgb(0x58, 0x6e, 0x75),
+                status_archived: Color::Rgb(0x58, 0x6e, 0x75),
+                priority_high: Color::Rgb(0xdc, 0x32, 0x2f),
+                priority_medium: Color::Rgb(0x83, 0x94, 0x96),
+                priority_low: Color::Rgb(0x58, 0x6e, 0x75),
+            }),
+            "gruvbox" => Some(Theme {
+                text: Color::Rgb(0xeb, 0xdb, 0xb2),
+                border: Color::Rgb(0xa8, 0x99, 0x84),
+                accent: Color::Rgb(0xfa, 0xbd, 0x2f),
+                selection_fg: Color::Rgb(0x28, 0x28, 0x28),
+                selection_bg: Color::Rgb(0xfa, 0xbd, 0x2f),
+                muted: Color::Rgb(0x92, 0x83, 0x74),
+                error: Color::Rgb(0xfb, 0x49, 0x34),
+                warning: Color::Rgb(0xfa, 0xbd, 0x2f),
+                success: Color::Rgb(0xb8, 0xbb, 0x26),
+                title: Color::Rgb(0x83, 0xa5, 0x98),
+                search_match: Color::Rgb(0xfe, 0x80, 0x19),
+                timer: Color::Rgb(0xd3, 0x86, 0x9b),
+                todo_count: Color::Rgb(0x8e, 0xc0, 0x7c),
+                status_active: Color::Rgb(0xb8, 0xbb, 0x26),
+                status_on_hold: Color::Rgb(0xfa, 0xbd, 0x2f),
+                status_done: Color::Rgb(0x83, 0xa5, 0x98),
+                status_abandoned: Color::Rgb(0x92, 0x83, 0x74),
+                status_archived: Color::Rgb(0x92, 0x83, 0x74),
+                priority_high: Color::Rgb(0xfb, 0x49, 0x34),
+                priority_medium: Color::Rgb(0xeb, 0xdb, 0xb2),
+                priority_low: Color::Rgb(0x92, 0x83, 0x74),
+            }),
+            "monochrome" => Some(Theme {
+                text: Color::Reset,
+                border: Color::Reset,
+                accent: Color::Reset,
+                selection_fg: Color::Black,
+                selection_bg: Color::White,
+                muted: Color::Gray,
+                error: Color::Reset,
+                warning: Color::Reset,
+                success: Color::Reset,
+                title: Color::Reset,
+                search_match: Color::Reset,
+                timer: Color::Reset,
+                todo_count: Color::Reset,
+                status_active: Color::Reset,
+                status_on_hold: Color::Reset,
+                status_done: Color::Gray,
+                status_abandoned: Color::Gray,
+                status_archived: Color::Gray,
+                priority_high: Color::Reset,
+                priority_medium: Color::Reset,
+                priority_low: Color::Gray,
+            }),
+            // Bold white on black with an inverted selection, for low vision and bright rooms.
+            "high-contrast" => Some(Theme {
+                text: Color::White,
+                border: Color::White,
+                accent: Color::LightCyan,
+                selection_fg: Color::Black,
+                selection_bg: Color::White,
+                muted: Color::Gray,
+                error: Color::LightRed,
+                warning: Color::LightYellow,
+                success: Color::LightGreen,
+                title: Color::White,
+                search_match: Color::LightCyan,
+                timer: Color::LightMagenta,
+                todo_count: Color::LightCyan,
+                status_active: Color::White,
+                status_on_hold: Color::LightYellow,
+                status_done: Color::LightCyan,
+                status_abandoned: Color::Gray,
+                status_archived: Color::Gray,
+                priority_high: Color::LightRed,
+                priority_medium: Color::White,
+                priority_low: Color::Gray,
+            }),
+            // The Okabe-Ito palette, which stays distinguishable without telling red from green.
+            "deuteranopia" => Some(Theme {
+                text: Color::Rgb(0xee, 0xee, 0xee),
+                border: Color::Rgb(0x99, 0x99, 0x99),
+                accent: Color::Rgb(0x56, 0xb4, 0xe9),
+                selection_fg: Color::Rgb(0x00, 0x00, 0x00),
+                selection_bg: Color::Rgb(0x56, 0xb4, 0xe9),
+                muted: Color::Rgb(0x88, 0x88, 0x88),
+                error: Color::Rgb(0xd5, 0x5e, 0x00),
+                warning: Color::Rgb(0xe6, 0x9f, 0x00),
+                success: Color::Rgb(0x00, 0x72, 0xb2),
+                title: Color::Rgb(0x56, 0xb4, 0xe9),
+                search_match: Color::Rgb(0xf0, 0xe4, 0x42),
+                timer: Color::Rgb(0xcc, 0x79, 0xa7),
+                todo_count: Color::Rgb(0x56, 0xb4, 0xe9),
+                status_active: Color::Rgb(0x00, 0x72, 0xb2),
+                status_on_hold: Color::Rgb(0xe6, 0x9f, 0x00),
+                status_done: Color::Rgb(0x56, 0xb4, 0xe9),
+                status_abandoned: Color::Rgb(0x88, 0x88, 0x88),
+                status_archived: Color::Rgb(0x88, 0x88, 0x88),
+                priority_high: Color::Rgb(0xd5, 0x5e, 0x00),
+                priority_medium: Color::Rgb(0xee, 0xee, 0xee),
+                priority_low: Color::Rgb(0x88, 0x88, 0x88),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Loads the named built-in theme and applies the user's color overrides on top. Colors
+    /// can be given by name, like `"Cyan"`, or in hex, like `"#2aa198"`. RGB colors are turned
+    /// into the closest one the terminal can show.
+    pub fn load(name: &str, overrides: &serde_json::Map<String, serde_json::Value>) -> Result<Theme, Error> {
+        let base = Theme::builtin(name).ok_or_else(|| Error::UnknownTheme(name.to_string()))?;
+        let mut value = serde_json::to_value(base)?;
+        if let serde_json::Value::Object(fields) = &mut value {
+            for (key, color) in overrides {
+                let color = match color.as_str().and_then(parse_hex) {
+                    Some(color) => serde_json::to_value(color)?,
+                    None => color.clone(),
+                };
+                fields.insert(key.clone(), color);
+            }
+            let support = ColorSupport::detect();
+            for color in fields.values_mut() {
+                if let Ok(parsed) = serde_json::from_value::<Color>(color.clone()) {
+                    *color = serde_json::to_value(support.downgrade(parsed))?;
+                }
+            }
+        }
+        serde_json::from_value(value).map_err(Error::ParseConfigError)
+    }
+
+    pub fn status(&self, status: Status) -> Color {
+        match status {
+            Status::Active => self.status_active,
+            Status::OnHold => self.status_on_hold,
+            Status::Done => self.status_done,
+            Status::Abandoned => self.status_abandoned,
+            Status::Archived => self.status_archived,
+        }
+    }
+
+    pub fn priority(&self, priority: Priority) -> Color {
+        match priority {
+            Priority::High => self.priority_high,
+            Priority::Medium => self.priority_medium,
+            Priority::Low => self.priority_low,
+        }
+    }
+
+    pub fn text(&self) -> Style {
+        Style::default().fg(self.text)
+    }
+
+    pub fn muted(&self) -> Style {
+        Style::default().fg(self.muted)
+    }
+
+    pub fn error(&self) -> Style {
+        Style::default().fg(self.error)
+    }
+
+    pub fn selection(&self) -> Style {
+        Style::default()
+            .bg(self.selection_bg)
+            .fg(self.selection_fg)
+            .add_modifier(Modifier::BOLD)
+    }
+
+    /// Border color of a pane, highlighted when it has the keyboard focus.
+    pub fn border(&self, focused: bool) -> Style {
+        Style::default().fg(if focused { self.accent } else { self.border })
+    }
+}
+
+/// Parses a color like `#2aa198`.
+fn parse_hex(text: &str) -> Option<Color> {
+    let hex = text.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// How many colors the terminal can show.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum ColorSupport {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+}
+
+/// The 16 basic colors with their usual RGB values.
+const ANSI_16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// The levels of each channel in the 6x6x6 color cube of 256-color terminals.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorSupport {
+    /// Guesses from `COLORTERM` and `TERM`, the same way most terminal programs do.
+    fn detect() -> ColorSupport {
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        let term = std::env::var("TERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            ColorSupport::TrueColor
+        } else if term.contains("256color") {
+            ColorSupport::Ansi256
+        } else {
+            ColorSupport::Ansi16
+        }
+    }
+
+    /// The closest color the terminal can show. Only RGB colors are changed.
+    fn downgrade(self, color: Color) -> Color {
+        let (r, g, b) = match color {
+            Color::Rgb(r, g, b) => (r, g, b),
+            _ => return color,
+        };
+        match self {
+            ColorSupport::TrueColor => color,
+            ColorSupport::Ansi256 => Color::Indexed(nearest_256(r, g, b)),
+            ColorSupport::Ansi16 => ANSI_16
+                .iter()
+                .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+                .map(|(color, _)| *color)
+                .unwrap_or(Color::Reset),
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let channel = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    channel(a.0, b.0) + channel(a.1, b.1) + channel(a.2, b.2)
+}
+
+/// Index of the closest entry of the 256-color palette, from the color cube or the grays.
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |x: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - x as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube_index = 16 + 36 * ri + 6 * gi + bi;
+
+    let average = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = ((average.saturating_sub(8) + 5) / 10).min(23);
+    let gray_level = (8 + 10 * gray_step) as u8;
+    let gray = (gray_level, gray_level, gray_level);
+
+    if distance((r, g, b), gray) < distance((r, g, b), cube) {
+        232 + gray_step as u8
+    } else {
+        cube_index as u8
+    }
+}
@@ -0,0 +1,47 @@
+use std::process::{Command, Stdio};
+
+/// The tmux session for a project, named after it. tmux doesn't allow `.` and `:` in
+/// session names, so they become `_`.
+pub fn session_name(project: &str) -> String {
+    project.trim().replace(['.', ':'], "_")
+}
+
+/// Whether whisk runs inside tmux, where sessions are switched to rather than attached.
+pub fn inside() -> bool {
+    std::env::var_os("TMUX").is_some_and(|tmux| !tmux.is_empty())
+}
+
+/// Switches the client to the session, after creating it in `directory` if there's none.
+/// Only works inside tmux. The error is what to tell the user.
+pub fn switch(name: &str, directory: &str) -> Result<(), String> {
+    let target = format!("={}", name);
+    if !run(Command::new("tmux").args(["has-session", "-t", &target]))? {
+        let created = run(Command::new("tmux").args(["new-session", "-d", "-s", name, "-c", directory]))?;
+        if !created {
+            return Err(format!("can't create the tmux session '{}'", name));
+        }
+    }
+    if !run(Command::new("tmux").args(["switch-client", "-t", &target]))? {
+        return Err(format!("can't switch to the tmux session '{}'", name));
+    }
+    Ok(())
+}
+
+/// The command that attaches to the session, creating it in `directory` if there's none.
+/// It takes over the terminal until the session is detached or ends.
+pub fn attach_command(name: &str, directory: &str) -> Command {
+    let mut command = Command::new("tmux");
+    command.args(["new-session", "-A", "-s", name, "-c", directory]);
+    command
+}
+
+/// Runs a tmux command quietly, true if it succeeded.
+fn run(command: &mut Command) -> Result<bool, String> {
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .map_err(|err| format!("can't run tmux: {}", err))
+}
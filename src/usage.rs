@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+/// Size of everything in `path`, in bytes. Symlinks count as themselves and aren't
+/// followed; what can't be read is left out. `None` if `path` isn't a directory.
+pub fn directory_size(path: &Path) -> Option<u64> {
+    if !path.is_dir() {
+        return None;
+    }
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else {
+                total += metadata.len();
+            }
+        }
+    }
+    Some(total)
+}
+
+/// Like "1.4 GB".
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Measures directories one after another on a background thread, since large ones
+/// take a while.
+pub struct UsageScanner {
+    requests: Sender<String>,
+    results: Receiver<(String, Option<u64>)>,
+    pending: HashSet<String>,
+    /// Directories found missing, which aren't measured again during this run.
+    missing: HashSet<String>,
+}
+
+impl UsageScanner {
+    pub fn new() -> UsageScanner {
+        let (requests, queue) = mpsc::channel::<String>();
+        let (done, results) = mpsc::channel();
+        thread::spawn(move || {
+            for directory in queue {
+                let size = directory_size(Path::new(&directory));
+                if done.send((directory, size)).is_err() {
+                    break;
+                }
+            }
+        });
+        UsageScanner {
+            requests,
+            results,
+            pending: HashSet::new(),
+            missing: HashSet::new(),
+        }
+    }
+
+    /// Queues `directory` unless it's already waiting to be measured or missing.
+    pub fn request(&mut self, directory: &str) {
+        if self.pending.contains(directory) || self.missing.contains(directory) {
+            return;
+        }
+        if self.requests.send(directory.to_string()).is_ok() {
+            self.pending.insert(directory.to_string());
+        }
+    }
+
+    /// The directories measured since the last call with their sizes.
+    pub fn finished(&mut self) -> Vec<(String, u64)> {
+        let mut finished = Vec::new();
+        for (directory, size) in self.results.try_iter() {
+            self.pending.remove(&directory);
+            match size {
+                Some(size) => finished.push((directory, size)),
+                None => {
+                    self.missing.insert(directory);
+                }
+            }
+        }
+        finished
+    }
+}
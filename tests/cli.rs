@@ -0,0 +1,114 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A home directory of its own for a test, so whisk neither reads nor writes the real
+/// projects, config and state. Removed again when the test is done.
+struct Sandbox {
+    home: PathBuf,
+}
+
+impl Sandbox {
+    fn new() -> Sandbox {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let home = std::env::temp_dir().join(format!(
+            "whisk-test-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&home);
+        fs::create_dir_all(&home).expect("can create the sandbox");
+        Sandbox { home }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.home.join(name)
+    }
+
+    /// whisk with `args`, to be run once more is set up, like another `WHISK_DB`.
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_whisk-cli"));
+        command
+            .args(args)
+            .env("HOME", &self.home)
+            .env("WHISK_DB", self.path("db.json"))
+            .env("WHISK_CONFIG", self.path("config.json"));
+        command
+    }
+
+    fn whisk(&self, args: &[&str]) -> Output {
+        self.command(args).output().expect("can run whisk")
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.home);
+    }
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn import_without_a_source_is_a_usage_error() {
+    let sandbox = Sandbox::new();
+    let output = sandbox.whisk(&["import"]);
+    assert_eq!(output.status.code(), Some(64), "{}", stderr(&output));
+    assert!(stderr(&output).contains("missing source argument"));
+}
+
+#[test]
+fn read_only_mv_relocate_leaves_the_directory_alone() {
+    let sandbox = Sandbox::new();
+    let from = sandbox.path("before");
+    let to = sandbox.path("after");
+    fs::create_dir(&from).expect("can create the project directory");
+    let output = sandbox.whisk(&["add", from.to_str().unwrap(), "--name", "moving"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+
+    let output = sandbox.whisk(&["--read-only", "mv", "moving", to.to_str().unwrap(), "--relocate"]);
+    assert_eq!(output.status.code(), Some(1), "{}", stderr(&output));
+    assert!(stderr(&output).contains("read-only mode"));
+    assert!(from.is_dir());
+    assert!(!to.exists());
+}
+
+#[test]
+fn a_db_that_cant_be_created_exits_with_2() {
+    let sandbox = Sandbox::new();
+    // A directory can't be created inside a file.
+    let file = sandbox.path("file");
+    fs::write(&file, "").expect("can create the file");
+    let output = sandbox
+        .command(&["list"])
+        .env("WHISK_DB", file.join("db.json"))
+        .output()
+        .expect("can run whisk");
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+}
+
+#[test]
+fn read_only_list_doesnt_create_the_db() {
+    let sandbox = Sandbox::new();
+    let db = sandbox.path("sub").join("db.json");
+    let output = sandbox
+        .command(&["--read-only", "list"])
+        .env("WHISK_DB", &db)
+        .output()
+        .expect("can run whisk");
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(!sandbox.path("sub").exists());
+}
+
+#[test]
+fn add_with_a_corrupt_db_exits_with_2() {
+    let sandbox = Sandbox::new();
+    fs::write(sandbox.path("db.json"), "not json").expect("can write the DB");
+    let directory = sandbox.path("project");
+    fs::create_dir(&directory).expect("can create the project directory");
+    let output = sandbox.whisk(&["add", directory.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(2), "{}", stderr(&output));
+}